@@ -1,2 +1,21 @@
 pub const VAULT_SEED: &[u8] = b"vault";
 pub const VAULT_STATE_SEED: &[u8] = b"vault_state";
+pub const DELEGATE_SEED: &[u8] = b"delegate";
+pub const STAKE_SEED: &[u8] = b"stake";
+pub const VESTING_SEED: &[u8] = b"vesting";
+pub const PAYOUT_SEED: &[u8] = b"payout";
+pub const PROPOSAL_SEED: &[u8] = b"proposal";
+pub const MAX_SIGNERS: usize = 10;
+pub const RECOVERY_SEED: &[u8] = b"recovery";
+pub const MAX_GUARDIANS: usize = 5;
+// How long the owner has to veto a recovery once enough guardians approved it
+pub const RECOVERY_DELAY: i64 = 3 * 24 * 60 * 60;
+// How long a recovery has to reach its threshold before any guardian can cancel it
+pub const RECOVERY_TTL: i64 = 2 * RECOVERY_DELAY;
+// How long a withdraw proposal stays open before it goes stale
+pub const PROPOSAL_TTL: i64 = 7 * 24 * 60 * 60;
+// Length of a withdraw cap window, and how long a raised cap waits before applying.
+// Windows are fixed, not rolling: one opens with the first withdrawal after the
+// last one closed, so up to twice the cap can leave either side of a boundary.
+pub const CAP_WINDOW: i64 = 24 * 60 * 60;
+pub const CAP_RAISE_DELAY: i64 = 2 * 24 * 60 * 60;
@@ -7,4 +7,56 @@ pub enum VaultError {
     InsufficientFunds,
     #[msg("The User amount is invalid")]
     InvalidAmount,
+    #[msg("The vault is time-locked until its unlock time")]
+    VaultLocked,
+    #[msg("The unlock time must be in the future and later than the current one")]
+    InvalidUnlockTime,
+    #[msg("The withdrawal exceeds the delegate's remaining allowance")]
+    AllowanceExceeded,
+    #[msg("Multisig vaults only release funds through withdraw proposals")]
+    MultisigRequired,
+    #[msg("Signers must be unique, at most MAX_SIGNERS, with 1 <= threshold <= signers")]
+    InvalidMultisig,
+    #[msg("The vault already has multisig enabled")]
+    MultisigAlreadyEnabled,
+    #[msg("This signer has already approved the proposal")]
+    AlreadyApproved,
+    #[msg("The proposal does not have enough approvals yet")]
+    ThresholdNotMet,
+    #[msg("The proposal has expired")]
+    ProposalStale,
+    #[msg("Only the proposer can cancel a proposal before it expires")]
+    ProposalNotStale,
+    #[msg("The withdrawal exceeds the vault's cap for this window")]
+    WithdrawCapExceeded,
+    #[msg("The vault still holds lamports after being swept")]
+    VaultNotEmpty,
+    #[msg("Guardians must be unique, at most MAX_GUARDIANS, with 1 <= threshold <= guardians")]
+    InvalidGuardians,
+    #[msg("The recovery can't complete until its timelock has passed")]
+    RecoveryTimelocked,
+    #[msg("Withdraw the vault's stake before closing it")]
+    StakeActive,
+    #[msg("A payout needs a positive amount and period, and an end time in the future")]
+    InvalidSchedule,
+    #[msg("Nothing has accrued on this payout schedule yet")]
+    NothingAccrued,
+    #[msg("Vesting needs a positive amount and duration, with start <= cliff <= start + duration")]
+    InvalidVesting,
+    #[msg("Nothing has vested beyond what was already claimed")]
+    NothingVested,
+    #[msg("This grant was created as irrevocable")]
+    NotRevocable,
+    #[msg("The proposal moves a different asset than this instruction")]
+    ProposalAssetMismatch,
+    #[msg("Mints with a transfer fee can't be held in the token vault or used for vesting grants")]
+    TransferFeeMint,
+    #[msg("Mints with a transfer hook can't be held in the token vault")]
+    TransferHookMint,
+    #[msg("The allowance was approved by a previous owner of the vault")]
+    StaleAllowance,
+    #[msg("The recovery didn't reach its guardian threshold before it expired")]
+    RecoveryStale,
+    #[msg("Only the initiator can cancel a recovery before it expires")]
+    RecoveryNotStale,
 }
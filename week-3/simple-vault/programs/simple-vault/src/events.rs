@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct VaultInitialized {
+    pub vault_state: Pubkey,
+    pub creator: Pubkey,
+    pub vault_id: u64,
+    pub unlock_at: i64,
+    pub slot: u64,
+}
+
+// Totals are the vault's lifetime counters after this deposit or withdrawal
+#[event]
+pub struct VaultDeposited {
+    pub vault_state: Pubkey,
+    pub depositor: Pubkey,
+    pub amount: u64,
+    pub total_deposited: u64,
+    pub slot: u64,
+}
+
+// Emitted for every SOL outflow counted by the withdraw cap; `recipient` is where
+// the lamports went: the owner, a delegate, a proposal or payout recipient, or a grant
+#[event]
+pub struct VaultWithdrawn {
+    pub vault_state: Pubkey,
+    pub owner: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub total_withdrawn: u64,
+    pub slot: u64,
+}
+
+// Emitted for every `deposit_for`, so a creator can see who paid in
+#[event]
+pub struct VaultDepositedFor {
+    pub vault_state: Pubkey,
+    pub creator: Pubkey,
+    pub depositor: Pubkey,
+    pub vault_id: u64,
+    pub amount: u64,
+    pub total_deposited: u64,
+    pub slot: u64,
+}
+
+// Token counterpart of `VaultWithdrawn`; `recipient` owns the ATA the tokens went to
+#[event]
+pub struct VaultTokenWithdrawn {
+    pub vault_state: Pubkey,
+    pub mint: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub slot: u64,
+}
+
+// `paid` is the settlement, always everything the recipient was owed
+#[event]
+pub struct PayoutCancelled {
+    pub vault_state: Pubkey,
+    pub recipient: Pubkey,
+    pub paid: u64,
+    pub slot: u64,
+}
+
+// `swept` is everything the vault held, its rent included
+#[event]
+pub struct VaultClosed {
+    pub vault_state: Pubkey,
+    pub owner: Pubkey,
+    pub vault_id: u64,
+    pub swept: u64,
+    pub total_deposited: u64,
+    pub total_withdrawn: u64,
+    pub slot: u64,
+}
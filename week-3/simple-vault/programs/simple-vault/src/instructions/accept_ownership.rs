@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::VAULT_STATE_SEED, error::VaultError, state::VaultState};
+
+// The proposed owner signs to take over; the PDA addresses stay the same
+#[derive(Accounts)]
+pub struct AcceptOwnershipAccounts<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+impl<'info> AcceptOwnershipAccounts<'info> {
+    pub fn accept_ownership_handler(&mut self) -> Result<()> {
+        require!(
+            self.vault_state.pending_owner == Some(self.signer.key()),
+            VaultError::Unauthorized
+        );
+        self.vault_state.set_owner(self.signer.key());
+        Ok(())
+    }
+}
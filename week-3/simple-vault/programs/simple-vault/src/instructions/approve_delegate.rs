@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{DELEGATE_SEED, VAULT_STATE_SEED},
+    state::{DelegateAllowance, VaultState},
+};
+
+// Creates or replaces the allowance for `delegate`; replacing resets what was spent
+#[derive(Accounts)]
+#[instruction(delegate:Pubkey)]
+pub struct ApproveDelegateAccounts<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        init_if_needed,
+        payer=signer,
+        space=DelegateAllowance::DISCRIMINATOR.len()+DelegateAllowance::INIT_SPACE,
+        seeds=[DELEGATE_SEED,vault_state.key().as_ref(),delegate.as_ref()],
+        bump
+    )]
+    pub delegate_allowance: Account<'info, DelegateAllowance>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ApproveDelegateAccounts<'info> {
+    pub fn approve_delegate_handler(
+        &mut self,
+        delegate: Pubkey,
+        allowance: u64,
+        per_epoch: bool,
+        bump: &ApproveDelegateAccountsBumps,
+    ) -> Result<()> {
+        self.vault_state.check_owner(self.signer.key)?;
+        self.vault_state.check_single_signer()?;
+        self.delegate_allowance.set_inner(DelegateAllowance {
+            vault_state: self.vault_state.key(),
+            delegate,
+            allowance,
+            per_epoch,
+            spent: 0,
+            epoch: Clock::get()?.epoch,
+            owner_nonce: self.vault_state.owner_nonce,
+            bump: bump.delegate_allowance,
+        });
+        Ok(())
+    }
+}
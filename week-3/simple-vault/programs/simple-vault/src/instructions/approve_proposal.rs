@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{PROPOSAL_SEED, VAULT_STATE_SEED},
+    error::VaultError,
+    state::{VaultState, WithdrawProposal},
+};
+#[derive(Accounts)]
+pub struct ApproveProposalAccounts<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds=[
+            PROPOSAL_SEED,
+            vault_state.key().as_ref(),
+            proposal.id.to_le_bytes().as_ref()],
+        bump=proposal.bump
+    )]
+    pub proposal: Account<'info, WithdrawProposal>,
+}
+
+impl<'info> ApproveProposalAccounts<'info> {
+    pub fn approve_proposal_handler(&mut self) -> Result<()> {
+        self.vault_state.check_member(self.signer.key)?;
+        require!(!self.proposal.is_stale()?, VaultError::ProposalStale);
+        require!(
+            !self.proposal.approvals.contains(self.signer.key),
+            VaultError::AlreadyApproved
+        );
+        self.proposal.approvals.push(self.signer.key());
+        Ok(())
+    }
+}
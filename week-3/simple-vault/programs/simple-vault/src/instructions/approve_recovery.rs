@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{RECOVERY_SEED, VAULT_STATE_SEED},
+    error::VaultError,
+    state::{RecoveryRequest, VaultState},
+};
+#[derive(Accounts)]
+pub struct ApproveRecoveryAccounts<'info> {
+    pub guardian: Signer<'info>,
+
+    #[account(
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds=[RECOVERY_SEED,vault_state.key().as_ref()],
+        bump=recovery.bump
+    )]
+    pub recovery: Account<'info, RecoveryRequest>,
+}
+
+impl<'info> ApproveRecoveryAccounts<'info> {
+    pub fn approve_recovery_handler(&mut self) -> Result<()> {
+        self.vault_state.check_guardian(self.guardian.key)?;
+        require!(!self.recovery.is_stale()?, VaultError::RecoveryStale);
+        require!(
+            !self.recovery.approvals.contains(self.guardian.key),
+            VaultError::AlreadyApproved
+        );
+        self.recovery.approvals.push(self.guardian.key());
+        self.recovery.start_timelock(&self.vault_state)
+    }
+}
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{PAYOUT_SEED, VAULT_SEED, VAULT_STATE_SEED},
+    events::PayoutCancelled,
+    instructions::crank_payout::pay_out,
+    state::{PayoutSchedule, VaultState},
+};
+
+// Settles what the recipient has earned up to now, pro rata for the current period,
+// in full, then closes the schedule with its rent going to the owner
+#[derive(Accounts)]
+pub struct CancelPayoutAccounts<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds=[VAULT_SEED,vault_state.creator.as_ref(),vault_state.key().as_ref()],
+        bump=vault_state.vault_bump)]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close=signer,
+        has_one=recipient,
+        seeds=[PAYOUT_SEED,vault_state.key().as_ref(),recipient.key().as_ref()],
+        bump=payout.bump
+    )]
+    pub payout: Account<'info, PayoutSchedule>,
+
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CancelPayoutAccounts<'info> {
+    // The lock, the cap and the vault balance are all in the owner's hands, so a
+    // settlement they keep back fails the cancel instead of being written off
+    pub fn cancel_payout_handler(&mut self) -> Result<()> {
+        self.vault_state.check_owner(self.signer.key)?;
+        let owed = self.payout.owed_on_cancel(Clock::get()?.unix_timestamp)?;
+        msg!("Settling {} lamports to the recipient", owed);
+        if owed > 0 {
+            pay_out(
+                &mut self.vault_state,
+                &self.vault,
+                &self.recipient,
+                &self.system_program,
+                owed,
+            )?;
+        }
+        emit!(PayoutCancelled {
+            vault_state: self.vault_state.key(),
+            recipient: self.recipient.key(),
+            paid: owed,
+            slot: Clock::get()?.slot,
+        });
+        Ok(())
+    }
+}
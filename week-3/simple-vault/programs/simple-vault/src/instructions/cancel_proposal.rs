@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{PROPOSAL_SEED, VAULT_STATE_SEED},
+    error::VaultError,
+    state::{VaultState, WithdrawProposal},
+};
+
+// The proposer can cancel at any time, any other member once the proposal is stale
+#[derive(Accounts)]
+pub struct CancelProposalAccounts<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        close=proposer,
+        has_one=proposer,
+        seeds=[
+            PROPOSAL_SEED,
+            vault_state.key().as_ref(),
+            proposal.id.to_le_bytes().as_ref()],
+        bump=proposal.bump
+    )]
+    pub proposal: Account<'info, WithdrawProposal>,
+
+    #[account(mut)]
+    pub proposer: SystemAccount<'info>,
+}
+
+impl<'info> CancelProposalAccounts<'info> {
+    pub fn cancel_proposal_handler(&mut self) -> Result<()> {
+        if self.signer.key() != self.proposal.proposer {
+            self.vault_state.check_member(self.signer.key)?;
+            require!(self.proposal.is_stale()?, VaultError::ProposalNotStale);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{RECOVERY_SEED, VAULT_STATE_SEED},
+    error::VaultError,
+    state::{RecoveryRequest, VaultState},
+};
+
+// The initiator can cancel at any time, any other guardian once the request has
+// expired short of its threshold, so one guardian can't hold the recovery slot forever
+#[derive(Accounts)]
+pub struct CancelRecoveryAccounts<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        close=initiator,
+        has_one=initiator,
+        seeds=[RECOVERY_SEED,vault_state.key().as_ref()],
+        bump=recovery.bump
+    )]
+    pub recovery: Account<'info, RecoveryRequest>,
+
+    #[account(mut)]
+    pub initiator: SystemAccount<'info>,
+}
+
+impl<'info> CancelRecoveryAccounts<'info> {
+    pub fn cancel_recovery_handler(&mut self) -> Result<()> {
+        if self.signer.key() != self.recovery.initiator {
+            self.vault_state.check_guardian(self.signer.key)?;
+            require!(self.recovery.is_stale()?, VaultError::RecoveryNotStale);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::VESTING_SEED, error::VaultError, state::VestingGrant};
+
+// Pays the beneficiary what has vested so far; the last claim closes the grant
+#[derive(Accounts)]
+pub struct ClaimVestedAccounts<'info> {
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one=beneficiary,
+        has_one=funder,
+        constraint=grant.mint.is_none() @ VaultError::InvalidVesting,
+        seeds=[VESTING_SEED,grant.vault_state.as_ref(),beneficiary.key().as_ref()],
+        bump=grant.bump
+    )]
+    pub grant: Account<'info, VestingGrant>,
+
+    #[account(mut)]
+    pub funder: SystemAccount<'info>,
+}
+
+impl<'info> ClaimVestedAccounts<'info> {
+    pub fn claim_vested_handler(&mut self) -> Result<()> {
+        let amount = self.grant.claimable(Clock::get()?.unix_timestamp);
+        require!(amount > 0, VaultError::NothingVested);
+
+        self.grant.claimed += amount;
+        self.grant.sub_lamports(amount)?;
+        self.beneficiary.add_lamports(amount)?;
+
+        if self.grant.claimed == self.grant.total_amount {
+            self.grant.close(self.funder.to_account_info())?;
+        }
+        Ok(())
+    }
+}
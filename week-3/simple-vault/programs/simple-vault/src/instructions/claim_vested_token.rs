@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{
+        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+        TransferChecked,
+    },
+};
+
+use crate::{constants::VESTING_SEED, error::VaultError, state::VestingGrant};
+
+// Token version of `claim_vested`; the last claim sweeps whatever the grant ATA holds,
+// tokens sent to it directly included, then closes the grant and its ATA
+#[derive(Accounts)]
+pub struct ClaimVestedTokenAccounts<'info> {
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one=beneficiary,
+        has_one=funder,
+        constraint=grant.mint == Some(mint.key()) @ VaultError::InvalidVesting,
+        seeds=[VESTING_SEED,grant.vault_state.as_ref(),beneficiary.key().as_ref(),mint.key().as_ref()],
+        bump=grant.bump
+    )]
+    pub grant: Account<'info, VestingGrant>,
+
+    #[account(mint::token_program=token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint=mint,
+        associated_token::authority=grant,
+        associated_token::token_program=token_program
+    )]
+    pub grant_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer=beneficiary,
+        associated_token::mint=mint,
+        associated_token::authority=beneficiary,
+        associated_token::token_program=token_program
+    )]
+    pub beneficiary_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub funder: SystemAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ClaimVestedTokenAccounts<'info> {
+    pub fn claim_vested_token_handler(&mut self) -> Result<()> {
+        let amount = self.grant.claimable(Clock::get()?.unix_timestamp);
+        require!(amount > 0, VaultError::NothingVested);
+        self.grant.claimed += amount;
+        let last = self.grant.claimed == self.grant.total_amount;
+        let amount = if last { self.grant_ata.amount } else { amount };
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            VESTING_SEED,
+            self.grant.vault_state.as_ref(),
+            self.beneficiary.key.as_ref(),
+            self.mint.to_account_info().key.as_ref(),
+            &[self.grant.bump],
+        ]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            TransferChecked {
+                from: self.grant_ata.to_account_info(),
+                mint: self.mint.to_account_info(),
+                to: self.beneficiary_ata.to_account_info(),
+                authority: self.grant.to_account_info(),
+            },
+            signer_seeds,
+        );
+        transfer_checked(cpi_ctx, amount, self.mint.decimals)?;
+
+        if last {
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                CloseAccount {
+                    account: self.grant_ata.to_account_info(),
+                    destination: self.funder.to_account_info(),
+                    authority: self.grant.to_account_info(),
+                },
+                signer_seeds,
+            );
+            close_account(cpi_ctx)?;
+            self.grant.close(self.funder.to_account_info())?;
+        }
+        Ok(())
+    }
+}
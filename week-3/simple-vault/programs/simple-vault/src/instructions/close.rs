@@ -3,7 +3,12 @@ use anchor_lang::{
     system_program::{transfer, Transfer},
 };
 
-use crate::{constants::VAULT_SEED, state::VaultState};
+use crate::{
+    constants::{VAULT_SEED, VAULT_STATE_SEED},
+    error::VaultError,
+    events::VaultClosed,
+    state::VaultState,
+};
 #[derive(Accounts)]
 pub struct CloseAccounts<'info> {
     #[account(mut)]
@@ -11,13 +16,17 @@ pub struct CloseAccounts<'info> {
 
     #[account(
         mut,
-        seeds=[b"vault_state",signer.key().as_ref()],bump,close=signer
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump,close=signer
     )]
     pub vault_state: Account<'info, VaultState>,
 
     #[account(
         mut,
-        seeds=[b"vault",signer.key().as_ref(),vault_state.key().as_ref()],
+        seeds=[VAULT_SEED,vault_state.creator.as_ref(),vault_state.key().as_ref()],
         bump,)]
     pub vault: SystemAccount<'info>,
 
@@ -25,10 +34,20 @@ pub struct CloseAccounts<'info> {
 }
 impl<'info> CloseAccounts<'info> {
     pub fn close_handler(&mut self) -> Result<()> {
+        self.vault_state.check_owner(self.signer.key)?;
+        self.vault_state.check_unlocked()?;
+        require!(self.vault_state.staked == 0, VaultError::StakeActive);
         let amount = self.vault.try_lamports()?;
+        let rent_exempt = Rent::get()?.minimum_balance(self.vault.data_len());
+        // A multisig vault can be closed once proposals have emptied it down to its rent
+        if self.vault_state.is_multisig() {
+            require!(amount <= rent_exempt, VaultError::MultisigRequired);
+        }
+        // The vault's own rent doesn't count against the withdraw cap
+        self.vault_state.spend(amount.saturating_sub(rent_exempt))?;
         let signer_seeds: &[&[&[u8]]] = &[&[
             VAULT_SEED,
-            self.signer.key.as_ref(),
+            self.vault_state.creator.as_ref(),
             self.vault_state.to_account_info().key.as_ref(),
             &[self.vault_state.vault_bump],
         ]];
@@ -41,6 +60,18 @@ impl<'info> CloseAccounts<'info> {
             signer_seeds,
         );
         transfer(cpi_ctx, amount)?;
+
+        require!(self.vault.lamports() == 0, VaultError::VaultNotEmpty);
+        msg!("Swept {} lamports to the owner", amount);
+        emit!(VaultClosed {
+            vault_state: self.vault_state.key(),
+            owner: self.signer.key(),
+            vault_id: self.vault_state.vault_id,
+            swept: amount,
+            total_deposited: self.vault_state.total_deposited,
+            total_withdrawn: self.vault_state.total_withdrawn,
+            slot: Clock::get()?.slot,
+        });
         Ok(())
     }
 }
@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{
+        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+        TransferChecked,
+    },
+};
+
+use crate::{
+    constants::VAULT_STATE_SEED,
+    error::VaultError,
+    events::VaultTokenWithdrawn,
+    state::{check_vault_mint, VaultState},
+};
+#[derive(Accounts)]
+pub struct CloseTokenVaultAccounts<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(mint::token_program=token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint=mint,
+        associated_token::authority=vault_state,
+        associated_token::token_program=token_program
+    )]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer=signer,
+        associated_token::mint=mint,
+        associated_token::authority=signer,
+        associated_token::token_program=token_program
+    )]
+    pub signer_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CloseTokenVaultAccounts<'info> {
+    // Sweeps the remaining balance to the creator and returns the ATA rent
+    pub fn close_token_vault_handler(&mut self) -> Result<()> {
+        self.vault_state.check_owner(self.signer.key)?;
+        self.vault_state.check_unlocked()?;
+        check_vault_mint(&self.mint)?;
+        // A multisig vault can drop an empty token vault, but not sweep one
+        if self.vault_state.is_multisig() {
+            require!(self.vault_ata.amount == 0, VaultError::MultisigRequired);
+        }
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            VAULT_STATE_SEED,
+            self.vault_state.creator.as_ref(),
+            &self.vault_state.vault_id.to_le_bytes(),
+            &[self.vault_state.state_bump],
+        ]];
+        if self.vault_ata.amount > 0 {
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.vault_ata.to_account_info(),
+                    mint: self.mint.to_account_info(),
+                    to: self.signer_ata.to_account_info(),
+                    authority: self.vault_state.to_account_info(),
+                },
+                signer_seeds,
+            );
+            transfer_checked(cpi_ctx, self.vault_ata.amount, self.mint.decimals)?;
+            emit!(VaultTokenWithdrawn {
+                vault_state: self.vault_state.key(),
+                mint: self.mint.key(),
+                recipient: self.signer.key(),
+                amount: self.vault_ata.amount,
+                slot: Clock::get()?.slot,
+            });
+        }
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: self.vault_ata.to_account_info(),
+                destination: self.signer.to_account_info(),
+                authority: self.vault_state.to_account_info(),
+            },
+            signer_seeds,
+        );
+        close_account(cpi_ctx)?;
+        Ok(())
+    }
+}
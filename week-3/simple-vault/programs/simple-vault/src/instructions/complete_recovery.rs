@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{RECOVERY_SEED, VAULT_STATE_SEED},
+    error::VaultError,
+    state::{RecoveryRequest, VaultState},
+};
+
+// Permissionless once the timelock has passed with enough guardian approvals
+#[derive(Accounts)]
+pub struct CompleteRecoveryAccounts<'info> {
+    #[account(
+        mut,
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        close=initiator,
+        has_one=initiator,
+        seeds=[RECOVERY_SEED,vault_state.key().as_ref()],
+        bump=recovery.bump
+    )]
+    pub recovery: Account<'info, RecoveryRequest>,
+
+    #[account(mut)]
+    pub initiator: SystemAccount<'info>,
+}
+
+impl<'info> CompleteRecoveryAccounts<'info> {
+    pub fn complete_recovery_handler(&mut self) -> Result<()> {
+        require!(
+            self.recovery.threshold_met(&self.vault_state),
+            VaultError::ThresholdNotMet
+        );
+        require!(
+            self.recovery.executable_at != 0
+                && Clock::get()?.unix_timestamp >= self.recovery.executable_at,
+            VaultError::RecoveryTimelocked
+        );
+        self.vault_state.set_owner(self.recovery.new_owner);
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+
+use crate::{
+    constants::{PAYOUT_SEED, VAULT_SEED, VAULT_STATE_SEED},
+    error::VaultError,
+    events::VaultWithdrawn,
+    state::{withdrawable_lamports, PayoutSchedule, VaultState},
+};
+
+// Permissionless: pays the recipient every full period that has accrued since the last crank
+#[derive(Accounts)]
+pub struct CrankPayoutAccounts<'info> {
+    #[account(
+        mut,
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds=[VAULT_SEED,vault_state.creator.as_ref(),vault_state.key().as_ref()],
+        bump=vault_state.vault_bump)]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        has_one=recipient,
+        seeds=[PAYOUT_SEED,vault_state.key().as_ref(),recipient.key().as_ref()],
+        bump=payout.bump
+    )]
+    pub payout: Account<'info, PayoutSchedule>,
+
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CrankPayoutAccounts<'info> {
+    pub fn crank_payout_handler(&mut self) -> Result<()> {
+        let periods = self.payout.due_periods(Clock::get()?.unix_timestamp);
+        require!(periods > 0, VaultError::NothingAccrued);
+        let amount = periods
+            .checked_mul(self.payout.amount_per_period)
+            .ok_or(VaultError::InvalidAmount)?;
+        self.payout.paid_periods += periods;
+        pay_out(
+            &mut self.vault_state,
+            &self.vault,
+            &self.recipient,
+            &self.system_program,
+            amount,
+        )
+    }
+}
+
+// Same checks as `withdraw`, minus the owner signature: the owner agreed to it when creating the schedule.
+// Schedules created before multisig was enabled stop paying along with `withdraw`.
+pub(crate) fn pay_out<'info>(
+    vault_state: &mut Account<'info, VaultState>,
+    vault: &SystemAccount<'info>,
+    recipient: &SystemAccount<'info>,
+    system_program: &Program<'info, System>,
+    amount: u64,
+) -> Result<()> {
+    require!(
+        withdrawable_lamports(vault)? >= amount,
+        VaultError::InsufficientFunds
+    );
+    vault_state.check_single_signer()?;
+    vault_state.check_unlocked()?;
+    vault_state.spend(amount)?;
+
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        VAULT_SEED,
+        vault_state.creator.as_ref(),
+        vault_state.to_account_info().key.as_ref(),
+        &[vault_state.vault_bump],
+    ]];
+    let cpi_ctx = CpiContext::new_with_signer(
+        system_program.to_account_info(),
+        Transfer {
+            from: vault.to_account_info(),
+            to: recipient.to_account_info(),
+        },
+        signer_seeds,
+    );
+    transfer(cpi_ctx, amount)?;
+
+    emit!(VaultWithdrawn {
+        vault_state: vault_state.key(),
+        owner: vault_state.owner,
+        recipient: recipient.key(),
+        amount,
+        total_withdrawn: vault_state.total_withdrawn,
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{PAYOUT_SEED, VAULT_STATE_SEED},
+    error::VaultError,
+    state::{PayoutSchedule, VaultState},
+};
+
+// One schedule per recipient and vault, starting now and ending after the last
+// full period before `end_at`
+#[derive(Accounts)]
+#[instruction(recipient:Pubkey)]
+pub struct CreatePayoutAccounts<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        init,
+        payer=signer,
+        space=PayoutSchedule::DISCRIMINATOR.len()+PayoutSchedule::INIT_SPACE,
+        seeds=[PAYOUT_SEED,vault_state.key().as_ref(),recipient.as_ref()],
+        bump
+    )]
+    pub payout: Account<'info, PayoutSchedule>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreatePayoutAccounts<'info> {
+    pub fn create_payout_handler(
+        &mut self,
+        recipient: Pubkey,
+        amount_per_period: u64,
+        period: i64,
+        end_at: i64,
+        bump: &CreatePayoutAccountsBumps,
+    ) -> Result<()> {
+        self.vault_state.check_owner(self.signer.key)?;
+        self.vault_state.check_single_signer()?;
+        let now = Clock::get()?.unix_timestamp;
+        let span = end_at.saturating_sub(now);
+        require!(
+            amount_per_period > 0 && period > 0 && span >= period,
+            VaultError::InvalidSchedule
+        );
+        // A trailing partial period would pay out pro rata on cancel but never on crank
+        let end_at = end_at - span % period;
+        self.payout.set_inner(PayoutSchedule {
+            vault_state: self.vault_state.key(),
+            recipient,
+            amount_per_period,
+            period,
+            start_at: now,
+            end_at,
+            paid_periods: 0,
+            bump: bump.payout,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::{VAULT_STATE_SEED, VESTING_SEED},
+    error::VaultError,
+    events::VaultTokenWithdrawn,
+    state::{check_vault_mint, VaultState, VestingGrant, VestingTerms},
+};
+
+// Token version of `create_vesting`: moves tokens from the vault ATA into an ATA owned by the grant
+#[derive(Accounts)]
+#[instruction(beneficiary:Pubkey)]
+pub struct CreateTokenVestingAccounts<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(mint::token_program=token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint=mint,
+        associated_token::authority=vault_state,
+        associated_token::token_program=token_program
+    )]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer=signer,
+        space=VestingGrant::DISCRIMINATOR.len()+VestingGrant::INIT_SPACE,
+        seeds=[VESTING_SEED,vault_state.key().as_ref(),beneficiary.as_ref(),mint.key().as_ref()],
+        bump
+    )]
+    pub grant: Account<'info, VestingGrant>,
+
+    // Anyone can create the ATA for the predictable grant address first; whatever
+    // it holds by then is swept out with the grant's last claim or revoke
+    #[account(
+        init_if_needed,
+        payer=signer,
+        associated_token::mint=mint,
+        associated_token::authority=grant,
+        associated_token::token_program=token_program
+    )]
+    pub grant_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateTokenVestingAccounts<'info> {
+    pub fn create_token_vesting_handler(
+        &mut self,
+        beneficiary: Pubkey,
+        terms: VestingTerms,
+        bump: &CreateTokenVestingAccountsBumps,
+    ) -> Result<()> {
+        self.vault_state.check_owner(self.signer.key)?;
+        self.vault_state.check_single_signer()?;
+        self.vault_state.check_unlocked()?;
+        terms.validate()?;
+        // The grant ATA could never be closed with fees withheld in it, and
+        // claims don't forward hook accounts either
+        check_vault_mint(&self.mint)?;
+        require!(
+            self.vault_ata.amount >= terms.amount,
+            VaultError::InsufficientFunds
+        );
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            VAULT_STATE_SEED,
+            self.vault_state.creator.as_ref(),
+            &self.vault_state.vault_id.to_le_bytes(),
+            &[self.vault_state.state_bump],
+        ]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            TransferChecked {
+                from: self.vault_ata.to_account_info(),
+                mint: self.mint.to_account_info(),
+                to: self.grant_ata.to_account_info(),
+                authority: self.vault_state.to_account_info(),
+            },
+            signer_seeds,
+        );
+        transfer_checked(cpi_ctx, terms.amount, self.mint.decimals)?;
+        emit!(VaultTokenWithdrawn {
+            vault_state: self.vault_state.key(),
+            mint: self.mint.key(),
+            recipient: self.grant.key(),
+            amount: terms.amount,
+            slot: Clock::get()?.slot,
+        });
+
+        self.grant.set_inner(VestingGrant {
+            vault_state: self.vault_state.key(),
+            funder: self.signer.key(),
+            beneficiary,
+            mint: Some(self.mint.key()),
+            total_amount: terms.amount,
+            claimed: 0,
+            start_at: terms.start_at,
+            cliff_at: terms.cliff_at,
+            duration: terms.duration,
+            revocable: terms.revocable,
+            bump: bump.grant,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,105 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+
+use crate::{
+    constants::{VAULT_SEED, VAULT_STATE_SEED, VESTING_SEED},
+    error::VaultError,
+    events::VaultWithdrawn,
+    state::{withdrawable_lamports, VaultState, VestingGrant, VestingTerms},
+};
+
+// Moves `terms.amount` lamports out of the vault into a grant that vests to `beneficiary`
+#[derive(Accounts)]
+#[instruction(beneficiary:Pubkey)]
+pub struct CreateVestingAccounts<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds=[VAULT_SEED,vault_state.creator.as_ref(),vault_state.key().as_ref()],
+        bump=vault_state.vault_bump)]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        init,
+        payer=signer,
+        space=VestingGrant::DISCRIMINATOR.len()+VestingGrant::INIT_SPACE,
+        seeds=[VESTING_SEED,vault_state.key().as_ref(),beneficiary.as_ref()],
+        bump
+    )]
+    pub grant: Account<'info, VestingGrant>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateVestingAccounts<'info> {
+    pub fn create_vesting_handler(
+        &mut self,
+        beneficiary: Pubkey,
+        terms: VestingTerms,
+        bump: &CreateVestingAccountsBumps,
+    ) -> Result<()> {
+        self.vault_state.check_owner(self.signer.key)?;
+        self.vault_state.check_single_signer()?;
+        self.vault_state.check_unlocked()?;
+        terms.validate()?;
+        require!(
+            withdrawable_lamports(&self.vault)? >= terms.amount,
+            VaultError::InsufficientFunds
+        );
+        self.vault_state.spend(terms.amount)?;
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            VAULT_SEED,
+            self.vault_state.creator.as_ref(),
+            self.vault_state.to_account_info().key.as_ref(),
+            &[self.vault_state.vault_bump],
+        ]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            Transfer {
+                from: self.vault.to_account_info(),
+                to: self.grant.to_account_info(),
+            },
+            signer_seeds,
+        );
+        transfer(cpi_ctx, terms.amount)?;
+
+        emit!(VaultWithdrawn {
+            vault_state: self.vault_state.key(),
+            owner: self.vault_state.owner,
+            recipient: self.grant.key(),
+            amount: terms.amount,
+            total_withdrawn: self.vault_state.total_withdrawn,
+            slot: Clock::get()?.slot,
+        });
+
+        self.grant.set_inner(VestingGrant {
+            vault_state: self.vault_state.key(),
+            funder: self.signer.key(),
+            beneficiary,
+            mint: None,
+            total_amount: terms.amount,
+            claimed: 0,
+            start_at: terms.start_at,
+            cliff_at: terms.cliff_at,
+            duration: terms.duration,
+            revocable: terms.revocable,
+            bump: bump.grant,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use anchor_lang::{prelude::*, solana_program::program::invoke_signed};
+use solana_stake_interface as stake;
+
+use crate::{
+    constants::{STAKE_SEED, VAULT_SEED, VAULT_STATE_SEED},
+    state::VaultState,
+};
+
+// Starts the cooldown; `withdraw_stake` works once the stake is fully inactive
+#[derive(Accounts)]
+pub struct DeactivateStakeAccounts<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        seeds=[VAULT_SEED,vault_state.creator.as_ref(),vault_state.key().as_ref()],
+        bump=vault_state.vault_bump)]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: the vault's stake account, validated by the stake program
+    #[account(
+        mut,
+        seeds=[STAKE_SEED,vault_state.key().as_ref()],
+        bump
+    )]
+    pub stake_account: UncheckedAccount<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+    /// CHECK: stake program
+    #[account(address=stake::program::ID)]
+    pub stake_program: UncheckedAccount<'info>,
+}
+
+impl<'info> DeactivateStakeAccounts<'info> {
+    pub fn deactivate_stake_handler(&mut self) -> Result<()> {
+        self.vault_state.check_owner(self.signer.key)?;
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            VAULT_SEED,
+            self.vault_state.creator.as_ref(),
+            self.vault_state.to_account_info().key.as_ref(),
+            &[self.vault_state.vault_bump],
+        ]];
+        invoke_signed(
+            &stake::instruction::deactivate_stake(self.stake_account.key, self.vault.key),
+            &[
+                self.stake_account.to_account_info(),
+                self.clock.to_account_info(),
+                self.vault.to_account_info(),
+            ],
+            signer_seeds,
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke_signed, sysvar},
+    system_program::{
+        allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer,
+    },
+};
+use solana_stake_interface::{
+    self as stake,
+    state::{Authorized, Lockup, StakeStateV2},
+};
+
+use crate::{
+    constants::{STAKE_SEED, VAULT_SEED, VAULT_STATE_SEED},
+    error::VaultError,
+    state::{withdrawable_lamports, VaultState},
+};
+
+// Funds the vault's stake account from the vault and delegates it to `vote_account`.
+// The vault PDA is both staker and withdrawer, so the lamports never leave its control.
+#[derive(Accounts)]
+pub struct DelegateStakeAccounts<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds=[VAULT_SEED,vault_state.creator.as_ref(),vault_state.key().as_ref()],
+        bump=vault_state.vault_bump)]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: created here as a stake account, must not be one already
+    #[account(
+        mut,
+        seeds=[STAKE_SEED,vault_state.key().as_ref()],
+        bump
+    )]
+    pub stake_account: UncheckedAccount<'info>,
+
+    /// CHECK: validated by the stake program
+    pub vote_account: UncheckedAccount<'info>,
+
+    /// CHECK: legacy stake config, passed through to the stake program
+    pub stake_config: UncheckedAccount<'info>,
+
+    /// CHECK: stake history sysvar
+    #[account(address=sysvar::stake_history::ID)]
+    pub stake_history: UncheckedAccount<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+    pub rent: Sysvar<'info, Rent>,
+    /// CHECK: stake program
+    #[account(address=stake::program::ID)]
+    pub stake_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> DelegateStakeAccounts<'info> {
+    pub fn delegate_stake_handler(
+        &mut self,
+        amount: u64,
+        bump: &DelegateStakeAccountsBumps,
+    ) -> Result<()> {
+        self.vault_state.check_owner(self.signer.key)?;
+        self.vault_state.check_single_signer()?;
+        require!(amount > 0, VaultError::InvalidAmount);
+        require!(
+            withdrawable_lamports(&self.vault)? >= amount,
+            VaultError::InsufficientFunds
+        );
+
+        let vault_state_key = self.vault_state.key();
+        let vault_seeds: &[&[u8]] = &[
+            VAULT_SEED,
+            self.vault_state.creator.as_ref(),
+            vault_state_key.as_ref(),
+            &[self.vault_state.vault_bump],
+        ];
+        let stake_seeds: &[&[u8]] = &[STAKE_SEED, vault_state_key.as_ref(), &[bump.stake_account]];
+        let create_seeds = [vault_seeds, stake_seeds];
+
+        // `amount` covers the stake account's rent as well as the delegation
+        if self.stake_account.lamports() == 0 {
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                CreateAccount {
+                    from: self.vault.to_account_info(),
+                    to: self.stake_account.to_account_info(),
+                },
+                &create_seeds,
+            );
+            create_account(
+                cpi_ctx,
+                amount,
+                StakeStateV2::size_of() as u64,
+                &stake::program::ID,
+            )?;
+        } else {
+            // Anyone can send lamports to the address first, which `create_account`
+            // refuses, so fund, allocate and assign it in steps as Anchor's `init` does.
+            // The extra lamports are staked too and come back as rewards.
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.vault.to_account_info(),
+                    to: self.stake_account.to_account_info(),
+                },
+                &create_seeds,
+            );
+            transfer(cpi_ctx, amount)?;
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                Allocate {
+                    account_to_allocate: self.stake_account.to_account_info(),
+                },
+                &create_seeds,
+            );
+            allocate(cpi_ctx, StakeStateV2::size_of() as u64)?;
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                Assign {
+                    account_to_assign: self.stake_account.to_account_info(),
+                },
+                &create_seeds,
+            );
+            assign(cpi_ctx, &stake::program::ID)?;
+        }
+
+        let authorized = Authorized {
+            staker: self.vault.key(),
+            withdrawer: self.vault.key(),
+        };
+        invoke_signed(
+            &stake::instruction::initialize(
+                self.stake_account.key,
+                &authorized,
+                &Lockup::default(),
+            ),
+            &[
+                self.stake_account.to_account_info(),
+                self.rent.to_account_info(),
+            ],
+            &[],
+        )?;
+        invoke_signed(
+            &stake::instruction::delegate_stake(
+                self.stake_account.key,
+                self.vault.key,
+                self.vote_account.key,
+            ),
+            &[
+                self.stake_account.to_account_info(),
+                self.vote_account.to_account_info(),
+                self.clock.to_account_info(),
+                self.stake_history.to_account_info(),
+                self.stake_config.to_account_info(),
+                self.vault.to_account_info(),
+            ],
+            &[vault_seeds],
+        )?;
+
+        self.vault_state.staked = self
+            .vault_state
+            .staked
+            .checked_add(amount)
+            .ok_or(VaultError::InvalidAmount)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,88 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+
+use crate::{
+    constants::{DELEGATE_SEED, VAULT_SEED, VAULT_STATE_SEED},
+    error::VaultError,
+    events::VaultWithdrawn,
+    state::{withdrawable_lamports, DelegateAllowance, VaultState},
+};
+
+// Withdraw by an approved delegate, paid out to the delegate and capped by its allowance
+#[derive(Accounts)]
+pub struct DelegateWithdrawAccounts<'info> {
+    #[account(mut)]
+    pub delegate: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds=[DELEGATE_SEED,vault_state.key().as_ref(),delegate.key().as_ref()],
+        bump=delegate_allowance.bump
+    )]
+    pub delegate_allowance: Account<'info, DelegateAllowance>,
+
+    #[account(
+        mut,
+        seeds=[VAULT_SEED,vault_state.creator.as_ref(),vault_state.key().as_ref()],
+        bump=vault_state.vault_bump)]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> DelegateWithdrawAccounts<'info> {
+    pub fn delegate_withdraw_handler(&mut self, amount: u64) -> Result<()> {
+        require!(amount > 0, VaultError::InvalidAmount);
+        require!(
+            withdrawable_lamports(&self.vault)? >= amount,
+            VaultError::InsufficientFunds
+        );
+        // Allowances granted before multisig was enabled stop working with it
+        self.vault_state.check_single_signer()?;
+        self.vault_state.check_unlocked()?;
+        require!(
+            self.delegate_allowance.owner_nonce == self.vault_state.owner_nonce,
+            VaultError::StaleAllowance
+        );
+        self.delegate_allowance.spend(amount)?;
+        self.vault_state.spend(amount)?;
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            VAULT_SEED,
+            self.vault_state.creator.as_ref(),
+            self.vault_state.to_account_info().key.as_ref(),
+            &[self.vault_state.vault_bump],
+        ]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            Transfer {
+                from: self.vault.to_account_info(),
+                to: self.delegate.to_account_info(),
+            },
+            signer_seeds,
+        );
+        transfer(cpi_ctx, amount)?;
+
+        emit!(VaultWithdrawn {
+            vault_state: self.vault_state.key(),
+            owner: self.vault_state.owner,
+            recipient: self.delegate.key(),
+            amount,
+            total_withdrawn: self.vault_state.total_withdrawn,
+            slot: Clock::get()?.slot,
+        });
+        Ok(())
+    }
+}
@@ -6,6 +6,7 @@ use anchor_lang::{
 use crate::{
     constants::{VAULT_SEED, VAULT_STATE_SEED},
     error::VaultError,
+    events::VaultDeposited,
     state::VaultState,
 };
 #[derive(Accounts)]
@@ -15,13 +16,17 @@ pub struct Deposit<'info> {
 
     #[account(
         mut,
-        seeds=[VAULT_STATE_SEED,signer.key().as_ref()],bump
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump
     )]
     pub vault_state: Account<'info, VaultState>,
 
     #[account(
         mut,
-        seeds=[VAULT_SEED,signer.key().as_ref(),vault_state.key().as_ref()],
+        seeds=[VAULT_SEED,vault_state.creator.as_ref(),vault_state.key().as_ref()],
         bump)]
     pub vault: SystemAccount<'info>,
 
@@ -30,6 +35,7 @@ pub struct Deposit<'info> {
 
 impl<'info> Deposit<'info> {
     pub fn deposit_handler(&mut self, amount: u64) -> Result<()> {
+        self.vault_state.check_owner(self.signer.key)?;
         require!(amount > 0, VaultError::InvalidAmount);
         let cpi_ctx = CpiContext::new(
             self.system_program.to_account_info(),
@@ -39,6 +45,15 @@ impl<'info> Deposit<'info> {
             },
         );
         transfer(cpi_ctx, amount)?;
+
+        self.vault_state.record_deposit(amount)?;
+        emit!(VaultDeposited {
+            vault_state: self.vault_state.key(),
+            depositor: self.signer.key(),
+            amount,
+            total_deposited: self.vault_state.total_deposited,
+            slot: Clock::get()?.slot,
+        });
         Ok(())
     }
 }
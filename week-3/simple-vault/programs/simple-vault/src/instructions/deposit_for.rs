@@ -0,0 +1,63 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+
+use crate::{
+    constants::{VAULT_SEED, VAULT_STATE_SEED},
+    error::VaultError,
+    events::VaultDepositedFor,
+    state::VaultState,
+};
+
+// Same as `Deposit`, but the PDAs come from `creator` so anyone can pay in
+#[derive(Accounts)]
+#[instruction(creator:Pubkey)]
+pub struct DepositForAccounts<'info> {
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[
+            VAULT_STATE_SEED,
+            creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds=[VAULT_SEED,creator.as_ref(),vault_state.key().as_ref()],
+        bump=vault_state.vault_bump)]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> DepositForAccounts<'info> {
+    pub fn deposit_for_handler(&mut self, creator: Pubkey, amount: u64) -> Result<()> {
+        require!(amount > 0, VaultError::InvalidAmount);
+        let cpi_ctx = CpiContext::new(
+            self.system_program.to_account_info(),
+            Transfer {
+                from: self.depositor.to_account_info(),
+                to: self.vault.to_account_info(),
+            },
+        );
+        transfer(cpi_ctx, amount)?;
+        self.vault_state.record_deposit(amount)?;
+
+        emit!(VaultDepositedFor {
+            vault_state: self.vault_state.key(),
+            creator,
+            depositor: self.depositor.key(),
+            vault_id: self.vault_state.vault_id,
+            amount,
+            total_deposited: self.vault_state.total_deposited,
+            slot: Clock::get()?.slot,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::VAULT_STATE_SEED,
+    error::VaultError,
+    state::{check_vault_mint, VaultState},
+};
+#[derive(Accounts)]
+pub struct DepositTokenAccounts<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(mint::token_program=token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint=mint,
+        associated_token::authority=signer,
+        associated_token::token_program=token_program
+    )]
+    pub signer_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // Token vault: ATA owned by the vault_state PDA, one per mint
+    #[account(
+        init_if_needed,
+        payer=signer,
+        associated_token::mint=mint,
+        associated_token::authority=vault_state,
+        associated_token::token_program=token_program
+    )]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> DepositTokenAccounts<'info> {
+    pub fn deposit_token_handler(&mut self, amount: u64) -> Result<()> {
+        self.vault_state.check_owner(self.signer.key)?;
+        require!(amount > 0, VaultError::InvalidAmount);
+        check_vault_mint(&self.mint)?;
+        let cpi_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            TransferChecked {
+                from: self.signer_ata.to_account_info(),
+                mint: self.mint.to_account_info(),
+                to: self.vault_ata.to_account_info(),
+                authority: self.signer.to_account_info(),
+            },
+        );
+        transfer_checked(cpi_ctx, amount, self.mint.decimals)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{MAX_SIGNERS, VAULT_STATE_SEED},
+    error::VaultError,
+    state::{is_valid_key_set, VaultState},
+};
+
+// One-way switch: once enabled, SOL and tokens only leave the vault through withdraw
+// proposals, `propose_withdraw` for SOL and `propose_token_withdraw` for a token vault
+#[derive(Accounts)]
+pub struct EnableMultisigAccounts<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+impl<'info> EnableMultisigAccounts<'info> {
+    pub fn enable_multisig_handler(&mut self, signers: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        self.vault_state.check_owner(self.signer.key)?;
+        require!(
+            !self.vault_state.is_multisig(),
+            VaultError::MultisigAlreadyEnabled
+        );
+        require!(
+            is_valid_key_set(&signers, threshold, MAX_SIGNERS),
+            VaultError::InvalidMultisig
+        );
+        self.vault_state.signers = signers;
+        self.vault_state.threshold = threshold;
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+
+use crate::{
+    constants::{PROPOSAL_SEED, VAULT_SEED, VAULT_STATE_SEED},
+    error::VaultError,
+    events::VaultWithdrawn,
+    state::{withdrawable_lamports, VaultState, WithdrawProposal},
+};
+
+// Any member can execute once the threshold is met; the proposal rent goes back to the proposer
+#[derive(Accounts)]
+pub struct ExecuteProposalAccounts<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        close=proposer,
+        has_one=proposer,
+        has_one=recipient,
+        constraint=proposal.mint.is_none() @ VaultError::ProposalAssetMismatch,
+        seeds=[
+            PROPOSAL_SEED,
+            vault_state.key().as_ref(),
+            proposal.id.to_le_bytes().as_ref()],
+        bump=proposal.bump
+    )]
+    pub proposal: Account<'info, WithdrawProposal>,
+
+    #[account(
+        mut,
+        seeds=[VAULT_SEED,vault_state.creator.as_ref(),vault_state.key().as_ref()],
+        bump=vault_state.vault_bump)]
+    pub vault: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub proposer: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ExecuteProposalAccounts<'info> {
+    pub fn execute_proposal_handler(&mut self) -> Result<()> {
+        self.vault_state.check_member(self.signer.key)?;
+        require!(!self.proposal.is_stale()?, VaultError::ProposalStale);
+        require!(
+            self.proposal.approvals.len() >= usize::from(self.vault_state.threshold),
+            VaultError::ThresholdNotMet
+        );
+        self.vault_state.check_unlocked()?;
+        let amount = self.proposal.amount;
+        require!(
+            withdrawable_lamports(&self.vault)? >= amount,
+            VaultError::InsufficientFunds
+        );
+        self.vault_state.spend(amount)?;
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            VAULT_SEED,
+            self.vault_state.creator.as_ref(),
+            self.vault_state.to_account_info().key.as_ref(),
+            &[self.vault_state.vault_bump],
+        ]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            Transfer {
+                from: self.vault.to_account_info(),
+                to: self.recipient.to_account_info(),
+            },
+            signer_seeds,
+        );
+        transfer(cpi_ctx, amount)?;
+
+        emit!(VaultWithdrawn {
+            vault_state: self.vault_state.key(),
+            owner: self.vault_state.owner,
+            recipient: self.recipient.key(),
+            amount,
+            total_withdrawn: self.vault_state.total_withdrawn,
+            slot: Clock::get()?.slot,
+        });
+        Ok(())
+    }
+}
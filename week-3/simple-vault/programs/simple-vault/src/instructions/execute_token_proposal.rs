@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::{PROPOSAL_SEED, VAULT_STATE_SEED},
+    error::VaultError,
+    events::VaultTokenWithdrawn,
+    state::{VaultState, WithdrawProposal},
+};
+
+// Token version of `execute_proposal`. The withdraw cap counts lamports, so it
+// doesn't apply here; the executing member pays for the recipient's ATA if needed.
+#[derive(Accounts)]
+pub struct ExecuteTokenProposalAccounts<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        close=proposer,
+        has_one=proposer,
+        has_one=recipient,
+        constraint=proposal.mint == Some(mint.key()) @ VaultError::ProposalAssetMismatch,
+        seeds=[
+            PROPOSAL_SEED,
+            vault_state.key().as_ref(),
+            proposal.id.to_le_bytes().as_ref()],
+        bump=proposal.bump
+    )]
+    pub proposal: Account<'info, WithdrawProposal>,
+
+    #[account(mint::token_program=token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint=mint,
+        associated_token::authority=vault_state,
+        associated_token::token_program=token_program
+    )]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub recipient: SystemAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer=signer,
+        associated_token::mint=mint,
+        associated_token::authority=recipient,
+        associated_token::token_program=token_program
+    )]
+    pub recipient_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub proposer: SystemAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ExecuteTokenProposalAccounts<'info> {
+    pub fn execute_token_proposal_handler(&mut self) -> Result<()> {
+        self.vault_state.check_member(self.signer.key)?;
+        require!(!self.proposal.is_stale()?, VaultError::ProposalStale);
+        require!(
+            self.proposal.approvals.len() >= usize::from(self.vault_state.threshold),
+            VaultError::ThresholdNotMet
+        );
+        self.vault_state.check_unlocked()?;
+        let amount = self.proposal.amount;
+        require!(
+            self.vault_ata.amount >= amount,
+            VaultError::InsufficientFunds
+        );
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            VAULT_STATE_SEED,
+            self.vault_state.creator.as_ref(),
+            &self.vault_state.vault_id.to_le_bytes(),
+            &[self.vault_state.state_bump],
+        ]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            TransferChecked {
+                from: self.vault_ata.to_account_info(),
+                mint: self.mint.to_account_info(),
+                to: self.recipient_ata.to_account_info(),
+                authority: self.vault_state.to_account_info(),
+            },
+            signer_seeds,
+        );
+        transfer_checked(cpi_ctx, amount, self.mint.decimals)?;
+
+        emit!(VaultTokenWithdrawn {
+            vault_state: self.vault_state.key(),
+            mint: self.mint.key(),
+            recipient: self.recipient.key(),
+            amount,
+            slot: Clock::get()?.slot,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::VAULT_STATE_SEED, error::VaultError, state::VaultState};
+#[derive(Accounts)]
+pub struct ExtendLockAccounts<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+impl<'info> ExtendLockAccounts<'info> {
+    // The lock can only ever be pushed further out, never shortened or removed
+    pub fn extend_lock_handler(&mut self, unlock_at: i64) -> Result<()> {
+        self.vault_state.check_owner(self.signer.key)?;
+        self.vault_state.check_single_signer()?;
+        require!(
+            unlock_at > self.vault_state.unlock_at && unlock_at > Clock::get()?.unix_timestamp,
+            VaultError::InvalidUnlockTime
+        );
+        self.vault_state.unlock_at = unlock_at;
+        Ok(())
+    }
+}
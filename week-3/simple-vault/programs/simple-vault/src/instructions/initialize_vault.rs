@@ -5,9 +5,12 @@ use anchor_lang::{
 
 use crate::{
     constants::{VAULT_SEED, VAULT_STATE_SEED},
+    error::VaultError,
+    events::VaultInitialized,
     state::VaultState,
 };
 #[derive(Accounts)]
+#[instruction(vault_id:u64)]
 pub struct InitializeAccounts<'info> {
     #[account(mut)]
     pub signer: Signer<'info>,
@@ -16,7 +19,11 @@ pub struct InitializeAccounts<'info> {
         init,
         payer=signer,
         space=VaultState::DISCRIMINATOR.len()+VaultState::INIT_SPACE,
-        seeds=[VAULT_STATE_SEED,signer.key().as_ref()],bump
+        seeds=[
+            VAULT_STATE_SEED,
+            signer.key().as_ref(),
+            vault_id.to_le_bytes().as_ref()],
+        bump
     )]
     pub vault_state: Account<'info, VaultState>,
 
@@ -30,7 +37,16 @@ pub struct InitializeAccounts<'info> {
 }
 
 impl<'info> InitializeAccounts<'info> {
-    pub fn initialize_handler(&mut self, bump: &InitializeAccountsBumps) -> Result<()> {
+    pub fn initialize_handler(
+        &mut self,
+        vault_id: u64,
+        unlock_at: i64,
+        bump: &InitializeAccountsBumps,
+    ) -> Result<()> {
+        require!(
+            unlock_at == 0 || unlock_at > Clock::get()?.unix_timestamp,
+            VaultError::InvalidUnlockTime
+        );
         let rent_exempt = Rent::get()?.minimum_balance(self.vault.data_len());
 
         let cpi_ctx = CpiContext::new(
@@ -44,8 +60,35 @@ impl<'info> InitializeAccounts<'info> {
 
         self.vault_state.set_inner(VaultState {
             creator: self.signer.key(),
+            owner: self.signer.key(),
+            pending_owner: None,
+            owner_nonce: 0,
+            vault_id,
             state_bump: bump.vault_state,
             vault_bump: bump.vault,
+            unlock_at,
+            signers: Vec::new(),
+            threshold: 0,
+            proposal_count: 0,
+            withdraw_cap: 0,
+            window_start: 0,
+            window_spent: 0,
+            pending_cap: 0,
+            pending_cap_at: 0,
+            guardians: Vec::new(),
+            guardian_threshold: 0,
+            staked: 0,
+            total_deposited: 0,
+            total_withdrawn: 0,
+            last_activity_slot: Clock::get()?.slot,
+        });
+
+        emit!(VaultInitialized {
+            vault_state: self.vault_state.key(),
+            creator: self.signer.key(),
+            vault_id,
+            unlock_at,
+            slot: Clock::get()?.slot,
         });
         Ok(())
     }
@@ -0,0 +1,147 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+
+use crate::{
+    constants::{VAULT_SEED, VAULT_STATE_SEED},
+    state::{withdrawable_lamports, VaultState, VaultStateV0},
+};
+
+// Moves a vault created before vault ids (seeded by the creator alone) to the
+// seeds for `vault_id`, carrying its SOL across and closing the old vault_state
+#[derive(Accounts)]
+#[instruction(vault_id:u64)]
+pub struct MigrateVaultAccounts<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// CHECK: legacy layout that `Account<VaultState>` can't load, validated in the handler
+    #[account(
+        mut,
+        owner=crate::ID,
+        seeds=[VAULT_STATE_SEED,signer.key().as_ref()],bump
+    )]
+    pub legacy_vault_state: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds=[VAULT_SEED,signer.key().as_ref(),legacy_vault_state.key().as_ref()],
+        bump)]
+    pub legacy_vault: SystemAccount<'info>,
+
+    #[account(
+        init,
+        payer=signer,
+        space=VaultState::DISCRIMINATOR.len()+VaultState::INIT_SPACE,
+        seeds=[
+            VAULT_STATE_SEED,
+            signer.key().as_ref(),
+            vault_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds=[VAULT_SEED,signer.key().as_ref(),vault_state.key().as_ref()],
+        bump)]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateVaultAccounts<'info> {
+    pub fn migrate_vault_handler(
+        &mut self,
+        vault_id: u64,
+        bump: &MigrateVaultAccountsBumps,
+    ) -> Result<()> {
+        let legacy = self.load_legacy()?;
+
+        // Whatever the legacy vault holds moves across, which may be nothing
+        // if it was emptied or never funded past its first deposit
+        let amount = self.legacy_vault.lamports();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            VAULT_SEED,
+            self.signer.key.as_ref(),
+            self.legacy_vault_state.key.as_ref(),
+            &[legacy.vault_bump],
+        ]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            Transfer {
+                from: self.legacy_vault.to_account_info(),
+                to: self.vault.to_account_info(),
+            },
+            signer_seeds,
+        );
+        transfer(cpi_ctx, amount)?;
+
+        // Top the new vault up to its rent-exempt minimum, as `initialize` does
+        let rent_exempt = Rent::get()?.minimum_balance(self.vault.data_len());
+        let shortfall = rent_exempt.saturating_sub(self.vault.lamports());
+        if shortfall > 0 {
+            let cpi_ctx = CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.signer.to_account_info(),
+                    to: self.vault.to_account_info(),
+                },
+            );
+            transfer(cpi_ctx, shortfall)?;
+        }
+
+        self.vault_state.set_inner(VaultState {
+            creator: legacy.creator,
+            owner: legacy.creator,
+            pending_owner: None,
+            owner_nonce: 0,
+            vault_id,
+            state_bump: bump.vault_state,
+            vault_bump: bump.vault,
+            unlock_at: 0,
+            signers: Vec::new(),
+            threshold: 0,
+            proposal_count: 0,
+            withdraw_cap: 0,
+            window_start: 0,
+            window_spent: 0,
+            pending_cap: 0,
+            pending_cap_at: 0,
+            guardians: Vec::new(),
+            guardian_threshold: 0,
+            staked: 0,
+            // The legacy balance, less the rent the new vault keeps, counts as deposited
+            total_deposited: withdrawable_lamports(&self.vault)?,
+            total_withdrawn: 0,
+            last_activity_slot: Clock::get()?.slot,
+        });
+
+        self.close_legacy_state()
+    }
+
+    fn load_legacy(&self) -> Result<VaultStateV0> {
+        let data = self.legacy_vault_state.try_borrow_data()?;
+        require!(
+            data.len() == VaultState::DISCRIMINATOR.len() + VaultStateV0::LEN,
+            ErrorCode::AccountDidNotDeserialize
+        );
+        require!(
+            data.starts_with(VaultState::DISCRIMINATOR),
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+        Ok(VaultStateV0::deserialize(
+            &mut &data[VaultState::DISCRIMINATOR.len()..],
+        )?)
+    }
+
+    fn close_legacy_state(&self) -> Result<()> {
+        let legacy = self.legacy_vault_state.to_account_info();
+        self.signer.add_lamports(legacy.lamports())?;
+        legacy.sub_lamports(legacy.lamports())?;
+        legacy.assign(&System::id());
+        legacy.resize(0)?;
+        Ok(())
+    }
+}
@@ -1,9 +1,85 @@
+pub mod accept_ownership;
+pub mod approve_delegate;
+pub mod approve_proposal;
+pub mod approve_recovery;
+pub mod cancel_payout;
+pub mod cancel_proposal;
+pub mod cancel_recovery;
+pub mod claim_vested;
+pub mod claim_vested_token;
 pub mod close;
+pub mod close_token_vault;
+pub mod complete_recovery;
+pub mod crank_payout;
+pub mod create_payout;
+pub mod create_token_vesting;
+pub mod create_vesting;
+pub mod deactivate_stake;
+pub mod delegate_stake;
+pub mod delegate_withdraw;
 pub mod deposit;
+pub mod deposit_for;
+pub mod deposit_token;
+pub mod enable_multisig;
+pub mod execute_proposal;
+pub mod execute_token_proposal;
+pub mod extend_lock;
 pub mod initialize_vault;
+pub mod migrate_vault;
+pub mod propose_new_owner;
+pub mod propose_token_withdraw;
+pub mod propose_withdraw;
+pub mod revoke_delegate;
+pub mod revoke_token_vesting;
+pub mod revoke_vesting;
+pub mod set_guardians;
+pub mod set_withdraw_cap;
+pub mod start_recovery;
+pub mod veto_recovery;
 pub mod withdraw;
+pub mod withdraw_all;
+pub mod withdraw_stake;
+pub mod withdraw_token;
 
+pub use accept_ownership::*;
+pub use approve_delegate::*;
+pub use approve_proposal::*;
+pub use approve_recovery::*;
+pub use cancel_payout::*;
+pub use cancel_proposal::*;
+pub use cancel_recovery::*;
+pub use claim_vested::*;
+pub use claim_vested_token::*;
 pub use close::*;
+pub use close_token_vault::*;
+pub use complete_recovery::*;
+pub use crank_payout::*;
+pub use create_payout::*;
+pub use create_token_vesting::*;
+pub use create_vesting::*;
+pub use deactivate_stake::*;
+pub use delegate_stake::*;
+pub use delegate_withdraw::*;
 pub use deposit::*;
+pub use deposit_for::*;
+pub use deposit_token::*;
+pub use enable_multisig::*;
+pub use execute_proposal::*;
+pub use execute_token_proposal::*;
+pub use extend_lock::*;
 pub use initialize_vault::*;
+pub use migrate_vault::*;
+pub use propose_new_owner::*;
+pub use propose_token_withdraw::*;
+pub use propose_withdraw::*;
+pub use revoke_delegate::*;
+pub use revoke_token_vesting::*;
+pub use revoke_vesting::*;
+pub use set_guardians::*;
+pub use set_withdraw_cap::*;
+pub use start_recovery::*;
+pub use veto_recovery::*;
 pub use withdraw::*;
+pub use withdraw_all::*;
+pub use withdraw_stake::*;
+pub use withdraw_token::*;
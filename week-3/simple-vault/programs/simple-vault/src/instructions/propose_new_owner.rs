@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::VAULT_STATE_SEED, state::VaultState};
+
+// First half of a handover; proposing again replaces the pending owner
+#[derive(Accounts)]
+pub struct ProposeNewOwnerAccounts<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+impl<'info> ProposeNewOwnerAccounts<'info> {
+    pub fn propose_new_owner_handler(&mut self, new_owner: Pubkey) -> Result<()> {
+        self.vault_state.check_owner(self.signer.key)?;
+        self.vault_state.pending_owner = Some(new_owner);
+        Ok(())
+    }
+}
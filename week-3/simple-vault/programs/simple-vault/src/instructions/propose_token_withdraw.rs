@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    constants::{PROPOSAL_SEED, PROPOSAL_TTL, VAULT_STATE_SEED},
+    error::VaultError,
+    state::{check_vault_mint, VaultState, WithdrawProposal},
+};
+
+// Token version of `propose_withdraw`, drawing from the vault's ATA for `mint`.
+// Shares the proposal counter, so approve and cancel work the same for both.
+#[derive(Accounts)]
+pub struct ProposeTokenWithdrawAccounts<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer=proposer,
+        space=WithdrawProposal::DISCRIMINATOR.len()+WithdrawProposal::INIT_SPACE,
+        seeds=[
+            PROPOSAL_SEED,
+            vault_state.key().as_ref(),
+            vault_state.proposal_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, WithdrawProposal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ProposeTokenWithdrawAccounts<'info> {
+    pub fn propose_token_withdraw_handler(
+        &mut self,
+        recipient: Pubkey,
+        amount: u64,
+        bump: &ProposeTokenWithdrawAccountsBumps,
+    ) -> Result<()> {
+        self.vault_state.check_member(self.proposer.key)?;
+        require!(amount > 0, VaultError::InvalidAmount);
+        check_vault_mint(&self.mint)?;
+
+        let id = self.vault_state.proposal_count;
+        self.proposal.set_inner(WithdrawProposal {
+            vault_state: self.vault_state.key(),
+            proposer: self.proposer.key(),
+            id,
+            recipient,
+            amount,
+            mint: Some(self.mint.key()),
+            approvals: vec![self.proposer.key()],
+            expires_at: Clock::get()?.unix_timestamp + PROPOSAL_TTL,
+            bump: bump.proposal,
+        });
+        self.vault_state.proposal_count = id + 1;
+        Ok(())
+    }
+}
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{PROPOSAL_SEED, PROPOSAL_TTL, VAULT_STATE_SEED},
+    error::VaultError,
+    state::{VaultState, WithdrawProposal},
+};
+
+// Any member can propose; the proposer pays the rent and counts as the first approval
+#[derive(Accounts)]
+pub struct ProposeWithdrawAccounts<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        init,
+        payer=proposer,
+        space=WithdrawProposal::DISCRIMINATOR.len()+WithdrawProposal::INIT_SPACE,
+        seeds=[
+            PROPOSAL_SEED,
+            vault_state.key().as_ref(),
+            vault_state.proposal_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, WithdrawProposal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ProposeWithdrawAccounts<'info> {
+    pub fn propose_withdraw_handler(
+        &mut self,
+        recipient: Pubkey,
+        amount: u64,
+        bump: &ProposeWithdrawAccountsBumps,
+    ) -> Result<()> {
+        self.vault_state.check_member(self.proposer.key)?;
+        require!(amount > 0, VaultError::InvalidAmount);
+
+        let id = self.vault_state.proposal_count;
+        self.proposal.set_inner(WithdrawProposal {
+            vault_state: self.vault_state.key(),
+            proposer: self.proposer.key(),
+            id,
+            recipient,
+            amount,
+            mint: None,
+            approvals: vec![self.proposer.key()],
+            expires_at: Clock::get()?.unix_timestamp + PROPOSAL_TTL,
+            bump: bump.proposal,
+        });
+        self.vault_state.proposal_count = id + 1;
+        Ok(())
+    }
+}
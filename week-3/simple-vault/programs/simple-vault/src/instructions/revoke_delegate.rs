@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{DELEGATE_SEED, VAULT_STATE_SEED},
+    state::{DelegateAllowance, VaultState},
+};
+#[derive(Accounts)]
+pub struct RevokeDelegateAccounts<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        close=signer,
+        seeds=[
+            DELEGATE_SEED,
+            vault_state.key().as_ref(),
+            delegate_allowance.delegate.as_ref()],
+        bump=delegate_allowance.bump
+    )]
+    pub delegate_allowance: Account<'info, DelegateAllowance>,
+}
+
+impl<'info> RevokeDelegateAccounts<'info> {
+    // Closing the allowance account is the revocation; its rent goes back to the owner
+    pub fn revoke_delegate_handler(&mut self) -> Result<()> {
+        self.vault_state.check_owner(self.signer.key)?;
+        Ok(())
+    }
+}
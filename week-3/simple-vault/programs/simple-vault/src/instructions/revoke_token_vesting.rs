@@ -0,0 +1,135 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{
+        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+        TransferChecked,
+    },
+};
+
+use crate::{
+    constants::{VAULT_STATE_SEED, VESTING_SEED},
+    error::VaultError,
+    state::{VaultState, VestingGrant},
+};
+
+// Token version of `revoke_vesting`. Everything in the grant ATA beyond what has vested,
+// tokens sent to it directly included, goes back to the vault so the ATA can close.
+#[derive(Accounts)]
+pub struct RevokeTokenVestingAccounts<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(mint::token_program=token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint=mint,
+        associated_token::authority=vault_state,
+        associated_token::token_program=token_program
+    )]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        close=funder,
+        has_one=vault_state,
+        has_one=beneficiary,
+        has_one=funder,
+        constraint=grant.mint == Some(mint.key()) @ VaultError::InvalidVesting,
+        seeds=[VESTING_SEED,vault_state.key().as_ref(),beneficiary.key().as_ref(),mint.key().as_ref()],
+        bump=grant.bump
+    )]
+    pub grant: Account<'info, VestingGrant>,
+
+    #[account(
+        mut,
+        associated_token::mint=mint,
+        associated_token::authority=grant,
+        associated_token::token_program=token_program
+    )]
+    pub grant_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub beneficiary: SystemAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer=signer,
+        associated_token::mint=mint,
+        associated_token::authority=beneficiary,
+        associated_token::token_program=token_program
+    )]
+    pub beneficiary_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub funder: SystemAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RevokeTokenVestingAccounts<'info> {
+    pub fn revoke_token_vesting_handler(&mut self) -> Result<()> {
+        self.vault_state.check_owner(self.signer.key)?;
+        require!(self.grant.revocable, VaultError::NotRevocable);
+
+        let now = Clock::get()?.unix_timestamp;
+        let vested = self.grant.claimable(now);
+        let unvested = self.grant_ata.amount.saturating_sub(vested);
+        msg!(
+            "Paying {} vested tokens, returning {} to the vault",
+            vested,
+            unvested
+        );
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            VESTING_SEED,
+            self.grant.vault_state.as_ref(),
+            self.beneficiary.key.as_ref(),
+            self.mint.to_account_info().key.as_ref(),
+            &[self.grant.bump],
+        ]];
+        for (to, amount) in [
+            (self.beneficiary_ata.to_account_info(), vested),
+            (self.vault_ata.to_account_info(), unvested),
+        ] {
+            if amount == 0 {
+                continue;
+            }
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.grant_ata.to_account_info(),
+                    mint: self.mint.to_account_info(),
+                    to,
+                    authority: self.grant.to_account_info(),
+                },
+                signer_seeds,
+            );
+            transfer_checked(cpi_ctx, amount, self.mint.decimals)?;
+        }
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: self.grant_ata.to_account_info(),
+                destination: self.funder.to_account_info(),
+                authority: self.grant.to_account_info(),
+            },
+            signer_seeds,
+        );
+        close_account(cpi_ctx)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{VAULT_SEED, VAULT_STATE_SEED, VESTING_SEED},
+    error::VaultError,
+    state::{VaultState, VestingGrant},
+};
+
+// Pays the beneficiary what has vested, returns the unvested rest to the vault and closes the grant
+#[derive(Accounts)]
+pub struct RevokeVestingAccounts<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds=[VAULT_SEED,vault_state.creator.as_ref(),vault_state.key().as_ref()],
+        bump=vault_state.vault_bump)]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close=funder,
+        has_one=vault_state,
+        has_one=beneficiary,
+        has_one=funder,
+        constraint=grant.mint.is_none() @ VaultError::InvalidVesting,
+        seeds=[VESTING_SEED,vault_state.key().as_ref(),beneficiary.key().as_ref()],
+        bump=grant.bump
+    )]
+    pub grant: Account<'info, VestingGrant>,
+
+    #[account(mut)]
+    pub beneficiary: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub funder: SystemAccount<'info>,
+}
+
+impl<'info> RevokeVestingAccounts<'info> {
+    pub fn revoke_vesting_handler(&mut self) -> Result<()> {
+        self.vault_state.check_owner(self.signer.key)?;
+        require!(self.grant.revocable, VaultError::NotRevocable);
+
+        let now = Clock::get()?.unix_timestamp;
+        let vested = self.grant.claimable(now);
+        let unvested = self.grant.total_amount - self.grant.vested(now);
+        msg!(
+            "Paying {} vested lamports, returning {} to the vault",
+            vested,
+            unvested
+        );
+        self.grant.sub_lamports(vested + unvested)?;
+        self.beneficiary.add_lamports(vested)?;
+        self.vault.add_lamports(unvested)?;
+        self.vault_state.record_deposit(unvested)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{MAX_GUARDIANS, VAULT_STATE_SEED},
+    error::VaultError,
+    state::{is_valid_key_set, VaultState},
+};
+
+// Replaces the guardian set; an empty list with threshold 0 turns recovery off
+#[derive(Accounts)]
+pub struct SetGuardiansAccounts<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+impl<'info> SetGuardiansAccounts<'info> {
+    pub fn set_guardians_handler(&mut self, guardians: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        self.vault_state.check_owner(self.signer.key)?;
+        require!(
+            (guardians.is_empty() && threshold == 0)
+                || is_valid_key_set(&guardians, threshold, MAX_GUARDIANS),
+            VaultError::InvalidGuardians
+        );
+        self.vault_state.guardians = guardians;
+        self.vault_state.guardian_threshold = threshold;
+        Ok(())
+    }
+}
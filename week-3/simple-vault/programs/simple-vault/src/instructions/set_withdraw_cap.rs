@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::VAULT_STATE_SEED, state::VaultState};
+#[derive(Accounts)]
+pub struct SetWithdrawCapAccounts<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+impl<'info> SetWithdrawCapAccounts<'info> {
+    pub fn set_withdraw_cap_handler(&mut self, cap: u64) -> Result<()> {
+        self.vault_state.check_owner(self.signer.key)?;
+        // A cap of 1 would stall every proposal the signers approve
+        self.vault_state.check_single_signer()?;
+        self.vault_state.set_withdraw_cap(cap)
+    }
+}
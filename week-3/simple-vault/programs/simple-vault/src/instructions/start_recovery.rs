@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{RECOVERY_SEED, RECOVERY_TTL, VAULT_STATE_SEED},
+    state::{RecoveryRequest, VaultState},
+};
+
+// A guardian opens the recovery, paying its rent and counting as the first approval.
+// Only one recovery can be open per vault.
+#[derive(Accounts)]
+pub struct StartRecoveryAccounts<'info> {
+    #[account(mut)]
+    pub guardian: Signer<'info>,
+
+    #[account(
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        init,
+        payer=guardian,
+        space=RecoveryRequest::DISCRIMINATOR.len()+RecoveryRequest::INIT_SPACE,
+        seeds=[RECOVERY_SEED,vault_state.key().as_ref()],
+        bump
+    )]
+    pub recovery: Account<'info, RecoveryRequest>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> StartRecoveryAccounts<'info> {
+    pub fn start_recovery_handler(
+        &mut self,
+        new_owner: Pubkey,
+        bump: &StartRecoveryAccountsBumps,
+    ) -> Result<()> {
+        self.vault_state.check_guardian(self.guardian.key)?;
+        self.recovery.set_inner(RecoveryRequest {
+            vault_state: self.vault_state.key(),
+            initiator: self.guardian.key(),
+            new_owner,
+            approvals: vec![self.guardian.key()],
+            executable_at: 0,
+            expires_at: Clock::get()?.unix_timestamp + RECOVERY_TTL,
+            bump: bump.recovery,
+        });
+        self.recovery.start_timelock(&self.vault_state)
+    }
+}
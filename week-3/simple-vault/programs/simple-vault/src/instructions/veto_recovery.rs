@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{RECOVERY_SEED, VAULT_STATE_SEED},
+    state::{RecoveryRequest, VaultState},
+};
+
+// The owner proves the key isn't lost by cancelling; rent goes back to the initiator
+#[derive(Accounts)]
+pub struct VetoRecoveryAccounts<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        close=initiator,
+        has_one=initiator,
+        seeds=[RECOVERY_SEED,vault_state.key().as_ref()],
+        bump=recovery.bump
+    )]
+    pub recovery: Account<'info, RecoveryRequest>,
+
+    #[account(mut)]
+    pub initiator: SystemAccount<'info>,
+}
+
+impl<'info> VetoRecoveryAccounts<'info> {
+    pub fn veto_recovery_handler(&mut self) -> Result<()> {
+        self.vault_state.check_owner(self.signer.key)?;
+        Ok(())
+    }
+}
@@ -3,7 +3,12 @@ use anchor_lang::{
     system_program::{transfer, Transfer},
 };
 
-use crate::{constants::VAULT_SEED, error::VaultError, state::VaultState};
+use crate::{
+    constants::{VAULT_SEED, VAULT_STATE_SEED},
+    error::VaultError,
+    events::VaultWithdrawn,
+    state::{withdrawable_lamports, VaultState},
+};
 #[derive(Accounts)]
 pub struct WithdrawAccounts<'info> {
     #[account(mut)]
@@ -11,13 +16,17 @@ pub struct WithdrawAccounts<'info> {
 
     #[account(
         mut,
-        seeds=[b"vault_state",signer.key().as_ref()],bump
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump
     )]
     pub vault_state: Account<'info, VaultState>,
 
     #[account(
         mut,
-        seeds=[b"vault",signer.key().as_ref(),vault_state.key().as_ref()],
+        seeds=[VAULT_SEED,vault_state.creator.as_ref(),vault_state.key().as_ref()],
         bump)]
     pub vault: SystemAccount<'info>,
 
@@ -25,17 +34,22 @@ pub struct WithdrawAccounts<'info> {
 }
 impl<'info> WithdrawAccounts<'info> {
     pub fn withdraw_handler(&mut self, amount: u64) -> Result<()> {
-        require!(
-            self.vault.lamports() >= amount,
-            VaultError::InsufficientFunds
-        );
-        require!(
-            self.signer.key() == self.vault_state.creator.key(),
-            VaultError::Unauthorized
-        );
+        let liquid = withdrawable_lamports(&self.vault)?;
+        if liquid < amount {
+            msg!(
+                "{} lamports liquid, {} staked",
+                liquid,
+                self.vault_state.staked
+            );
+            return err!(VaultError::InsufficientFunds);
+        }
+        self.vault_state.check_owner(self.signer.key)?;
+        self.vault_state.check_single_signer()?;
+        self.vault_state.check_unlocked()?;
+        self.vault_state.spend(amount)?;
         let signer_seeds: &[&[&[u8]]] = &[&[
             VAULT_SEED,
-            self.signer.key.as_ref(),
+            self.vault_state.creator.as_ref(),
             self.vault_state.to_account_info().key.as_ref(),
             &[self.vault_state.vault_bump],
         ]];
@@ -48,6 +62,15 @@ impl<'info> WithdrawAccounts<'info> {
             signer_seeds,
         );
         transfer(cpi_ctx, amount)?;
+
+        emit!(VaultWithdrawn {
+            vault_state: self.vault_state.key(),
+            owner: self.signer.key(),
+            recipient: self.signer.key(),
+            amount,
+            total_withdrawn: self.vault_state.total_withdrawn,
+            slot: Clock::get()?.slot,
+        });
         Ok(())
     }
 }
@@ -0,0 +1,71 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+
+use crate::{
+    constants::{VAULT_SEED, VAULT_STATE_SEED},
+    error::VaultError,
+    events::VaultWithdrawn,
+    state::{withdrawable_lamports, VaultState},
+};
+
+// Withdraws everything above the rent floor, leaving the vault open for more deposits
+#[derive(Accounts)]
+pub struct WithdrawAllAccounts<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds=[VAULT_SEED,vault_state.creator.as_ref(),vault_state.key().as_ref()],
+        bump)]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+impl<'info> WithdrawAllAccounts<'info> {
+    pub fn withdraw_all_handler(&mut self) -> Result<()> {
+        let amount = withdrawable_lamports(&self.vault)?;
+        require!(amount > 0, VaultError::InsufficientFunds);
+        self.vault_state.check_owner(self.signer.key)?;
+        self.vault_state.check_single_signer()?;
+        self.vault_state.check_unlocked()?;
+        self.vault_state.spend(amount)?;
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            VAULT_SEED,
+            self.vault_state.creator.as_ref(),
+            self.vault_state.to_account_info().key.as_ref(),
+            &[self.vault_state.vault_bump],
+        ]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            Transfer {
+                from: self.vault.to_account_info(),
+                to: self.signer.to_account_info(),
+            },
+            signer_seeds,
+        );
+        transfer(cpi_ctx, amount)?;
+
+        emit!(VaultWithdrawn {
+            vault_state: self.vault_state.key(),
+            owner: self.signer.key(),
+            recipient: self.signer.key(),
+            amount,
+            total_withdrawn: self.vault_state.total_withdrawn,
+            slot: Clock::get()?.slot,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,88 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke_signed, sysvar},
+};
+use solana_stake_interface as stake;
+
+use crate::{
+    constants::{STAKE_SEED, VAULT_SEED, VAULT_STATE_SEED},
+    state::VaultState,
+};
+
+// Pulls the whole stake account, rewards included, back into the vault once it is inactive
+#[derive(Accounts)]
+pub struct WithdrawStakeAccounts<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[
+            VAULT_STATE_SEED,
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()],
+        bump=vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds=[VAULT_SEED,vault_state.creator.as_ref(),vault_state.key().as_ref()],
+        bump=vault_state.vault_bump)]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: the vault's stake account, validated by the stake program
+    #[account(
+        mut,
+        seeds=[STAKE_SEED,vault_state.key().as_ref()],
+        bump
+    )]
+    pub stake_account: UncheckedAccount<'info>,
+
+    /// CHECK: stake history sysvar
+    #[account(address=sysvar::stake_history::ID)]
+    pub stake_history: UncheckedAccount<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+    /// CHECK: stake program
+    #[account(address=stake::program::ID)]
+    pub stake_program: UncheckedAccount<'info>,
+}
+
+impl<'info> WithdrawStakeAccounts<'info> {
+    pub fn withdraw_stake_handler(&mut self) -> Result<()> {
+        self.vault_state.check_owner(self.signer.key)?;
+        let amount = self.stake_account.lamports();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            VAULT_SEED,
+            self.vault_state.creator.as_ref(),
+            self.vault_state.to_account_info().key.as_ref(),
+            &[self.vault_state.vault_bump],
+        ]];
+        invoke_signed(
+            &stake::instruction::withdraw(
+                self.stake_account.key,
+                self.vault.key,
+                self.vault.key,
+                amount,
+                None,
+            ),
+            &[
+                self.stake_account.to_account_info(),
+                self.vault.to_account_info(),
+                self.clock.to_account_info(),
+                self.stake_history.to_account_info(),
+            ],
+            signer_seeds,
+        )?;
+
+        let rewards = amount.saturating_sub(self.vault_state.staked);
+        msg!(
+            "Withdrew {} lamports of stake, {} in rewards",
+            amount,
+            rewards
+        );
+        self.vault_state.staked = 0;
+        self.vault_state.record_deposit(rewards)?;
+        Ok(())
+    }
+}
@@ -1,29 +1,241 @@
 use anchor_lang::prelude::*;
 
 declare_id!("CmKVpLqQ7C5kGpWuQ6EiivXsEprdgfhk96rDtj4daavr");
-mod constants;
-mod error;
+pub mod constants;
+pub mod error;
+pub mod events;
 mod instructions;
-mod state;
+pub mod state;
 use instructions::*;
+use state::VestingTerms;
 #[program]
 pub mod simple_vault {
     use super::*;
 
-    pub fn initialize_vault(ctx: Context<InitializeAccounts>) -> Result<()> {
-        ctx.accounts.initialize_handler(&ctx.bumps)?;
+    pub fn initialize_vault(
+        ctx: Context<InitializeAccounts>,
+        vault_id: u64,
+        unlock_at: i64,
+    ) -> Result<()> {
+        ctx.accounts
+            .initialize_handler(vault_id, unlock_at, &ctx.bumps)?;
         Ok(())
     }
     pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
         ctx.accounts.deposit_handler(amount)?;
         Ok(())
     }
+    pub fn deposit_for(
+        ctx: Context<DepositForAccounts>,
+        creator: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.deposit_for_handler(creator, amount)?;
+        Ok(())
+    }
     pub fn withdraw(ctx: Context<WithdrawAccounts>, amount: u64) -> Result<()> {
         ctx.accounts.withdraw_handler(amount)?;
         Ok(())
     }
+    pub fn withdraw_all(ctx: Context<WithdrawAllAccounts>) -> Result<()> {
+        ctx.accounts.withdraw_all_handler()?;
+        Ok(())
+    }
     pub fn close(ctx: Context<CloseAccounts>) -> Result<()> {
         ctx.accounts.close_handler()?;
         Ok(())
     }
+    pub fn deposit_token(ctx: Context<DepositTokenAccounts>, amount: u64) -> Result<()> {
+        ctx.accounts.deposit_token_handler(amount)?;
+        Ok(())
+    }
+    pub fn withdraw_token(ctx: Context<WithdrawTokenAccounts>, amount: u64) -> Result<()> {
+        ctx.accounts.withdraw_token_handler(amount)?;
+        Ok(())
+    }
+    pub fn close_token_vault(ctx: Context<CloseTokenVaultAccounts>) -> Result<()> {
+        ctx.accounts.close_token_vault_handler()?;
+        Ok(())
+    }
+    pub fn migrate_vault(ctx: Context<MigrateVaultAccounts>, vault_id: u64) -> Result<()> {
+        ctx.accounts.migrate_vault_handler(vault_id, &ctx.bumps)?;
+        Ok(())
+    }
+    pub fn approve_delegate(
+        ctx: Context<ApproveDelegateAccounts>,
+        delegate: Pubkey,
+        allowance: u64,
+        per_epoch: bool,
+    ) -> Result<()> {
+        ctx.accounts
+            .approve_delegate_handler(delegate, allowance, per_epoch, &ctx.bumps)?;
+        Ok(())
+    }
+    pub fn revoke_delegate(ctx: Context<RevokeDelegateAccounts>) -> Result<()> {
+        ctx.accounts.revoke_delegate_handler()?;
+        Ok(())
+    }
+    pub fn delegate_withdraw(ctx: Context<DelegateWithdrawAccounts>, amount: u64) -> Result<()> {
+        ctx.accounts.delegate_withdraw_handler(amount)?;
+        Ok(())
+    }
+    pub fn extend_lock(ctx: Context<ExtendLockAccounts>, unlock_at: i64) -> Result<()> {
+        ctx.accounts.extend_lock_handler(unlock_at)?;
+        Ok(())
+    }
+    pub fn enable_multisig(
+        ctx: Context<EnableMultisigAccounts>,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        ctx.accounts.enable_multisig_handler(signers, threshold)?;
+        Ok(())
+    }
+    pub fn propose_withdraw(
+        ctx: Context<ProposeWithdrawAccounts>,
+        recipient: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .propose_withdraw_handler(recipient, amount, &ctx.bumps)?;
+        Ok(())
+    }
+    pub fn propose_token_withdraw(
+        ctx: Context<ProposeTokenWithdrawAccounts>,
+        recipient: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .propose_token_withdraw_handler(recipient, amount, &ctx.bumps)?;
+        Ok(())
+    }
+    pub fn approve_proposal(ctx: Context<ApproveProposalAccounts>) -> Result<()> {
+        ctx.accounts.approve_proposal_handler()?;
+        Ok(())
+    }
+    pub fn execute_proposal(ctx: Context<ExecuteProposalAccounts>) -> Result<()> {
+        ctx.accounts.execute_proposal_handler()?;
+        Ok(())
+    }
+    pub fn execute_token_proposal(ctx: Context<ExecuteTokenProposalAccounts>) -> Result<()> {
+        ctx.accounts.execute_token_proposal_handler()?;
+        Ok(())
+    }
+    pub fn cancel_proposal(ctx: Context<CancelProposalAccounts>) -> Result<()> {
+        ctx.accounts.cancel_proposal_handler()?;
+        Ok(())
+    }
+    pub fn set_withdraw_cap(ctx: Context<SetWithdrawCapAccounts>, cap: u64) -> Result<()> {
+        ctx.accounts.set_withdraw_cap_handler(cap)?;
+        Ok(())
+    }
+    pub fn propose_new_owner(
+        ctx: Context<ProposeNewOwnerAccounts>,
+        new_owner: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.propose_new_owner_handler(new_owner)?;
+        Ok(())
+    }
+    pub fn accept_ownership(ctx: Context<AcceptOwnershipAccounts>) -> Result<()> {
+        ctx.accounts.accept_ownership_handler()?;
+        Ok(())
+    }
+    pub fn set_guardians(
+        ctx: Context<SetGuardiansAccounts>,
+        guardians: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        ctx.accounts.set_guardians_handler(guardians, threshold)?;
+        Ok(())
+    }
+    pub fn start_recovery(ctx: Context<StartRecoveryAccounts>, new_owner: Pubkey) -> Result<()> {
+        ctx.accounts.start_recovery_handler(new_owner, &ctx.bumps)?;
+        Ok(())
+    }
+    pub fn approve_recovery(ctx: Context<ApproveRecoveryAccounts>) -> Result<()> {
+        ctx.accounts.approve_recovery_handler()?;
+        Ok(())
+    }
+    pub fn veto_recovery(ctx: Context<VetoRecoveryAccounts>) -> Result<()> {
+        ctx.accounts.veto_recovery_handler()?;
+        Ok(())
+    }
+    pub fn complete_recovery(ctx: Context<CompleteRecoveryAccounts>) -> Result<()> {
+        ctx.accounts.complete_recovery_handler()?;
+        Ok(())
+    }
+    pub fn cancel_recovery(ctx: Context<CancelRecoveryAccounts>) -> Result<()> {
+        ctx.accounts.cancel_recovery_handler()?;
+        Ok(())
+    }
+    pub fn delegate_stake(ctx: Context<DelegateStakeAccounts>, amount: u64) -> Result<()> {
+        ctx.accounts.delegate_stake_handler(amount, &ctx.bumps)?;
+        Ok(())
+    }
+    pub fn deactivate_stake(ctx: Context<DeactivateStakeAccounts>) -> Result<()> {
+        ctx.accounts.deactivate_stake_handler()?;
+        Ok(())
+    }
+    pub fn withdraw_stake(ctx: Context<WithdrawStakeAccounts>) -> Result<()> {
+        ctx.accounts.withdraw_stake_handler()?;
+        Ok(())
+    }
+    pub fn create_payout(
+        ctx: Context<CreatePayoutAccounts>,
+        recipient: Pubkey,
+        amount_per_period: u64,
+        period: i64,
+        end_at: i64,
+    ) -> Result<()> {
+        ctx.accounts.create_payout_handler(
+            recipient,
+            amount_per_period,
+            period,
+            end_at,
+            &ctx.bumps,
+        )?;
+        Ok(())
+    }
+    pub fn crank_payout(ctx: Context<CrankPayoutAccounts>) -> Result<()> {
+        ctx.accounts.crank_payout_handler()?;
+        Ok(())
+    }
+    pub fn cancel_payout(ctx: Context<CancelPayoutAccounts>) -> Result<()> {
+        ctx.accounts.cancel_payout_handler()?;
+        Ok(())
+    }
+    pub fn create_vesting(
+        ctx: Context<CreateVestingAccounts>,
+        beneficiary: Pubkey,
+        terms: VestingTerms,
+    ) -> Result<()> {
+        ctx.accounts
+            .create_vesting_handler(beneficiary, terms, &ctx.bumps)?;
+        Ok(())
+    }
+    pub fn claim_vested(ctx: Context<ClaimVestedAccounts>) -> Result<()> {
+        ctx.accounts.claim_vested_handler()?;
+        Ok(())
+    }
+    pub fn revoke_vesting(ctx: Context<RevokeVestingAccounts>) -> Result<()> {
+        ctx.accounts.revoke_vesting_handler()?;
+        Ok(())
+    }
+    pub fn create_token_vesting(
+        ctx: Context<CreateTokenVestingAccounts>,
+        beneficiary: Pubkey,
+        terms: VestingTerms,
+    ) -> Result<()> {
+        ctx.accounts
+            .create_token_vesting_handler(beneficiary, terms, &ctx.bumps)?;
+        Ok(())
+    }
+    pub fn claim_vested_token(ctx: Context<ClaimVestedTokenAccounts>) -> Result<()> {
+        ctx.accounts.claim_vested_token_handler()?;
+        Ok(())
+    }
+    pub fn revoke_token_vesting(ctx: Context<RevokeTokenVestingAccounts>) -> Result<()> {
+        ctx.accounts.revoke_token_vesting_handler()?;
+        Ok(())
+    }
 }
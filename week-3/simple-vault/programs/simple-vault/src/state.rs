@@ -1,8 +1,429 @@
 use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::spl_token_2022::{
+        self,
+        extension::{
+            transfer_fee::TransferFeeConfig, transfer_hook, BaseStateWithExtensions,
+            StateWithExtensions,
+        },
+        state::Mint as MintState,
+    },
+    token_interface::Mint,
+};
+
+use crate::{
+    constants::{CAP_RAISE_DELAY, CAP_WINDOW, MAX_GUARDIANS, MAX_SIGNERS, RECOVERY_DELAY},
+    error::VaultError,
+};
 #[derive(InitSpace)]
 #[account]
 pub struct VaultState {
+    // Key the PDAs were derived from; never changes, even when ownership does
     pub creator: Pubkey,
+    // Current owner, the only key allowed to run the owner-only instructions
+    pub owner: Pubkey,
+    // Set by `propose_new_owner` until that key calls `accept_ownership`
+    pub pending_owner: Option<Pubkey>,
+    // Bumped on every change of owner, so grants made by a previous owner can be told apart
+    pub owner_nonce: u64,
+    // Lets one creator run several vaults, part of the vault_state seeds
+    pub vault_id: u64,
     pub state_bump: u8,
     pub vault_bump: u8,
+    // Unix timestamp before which withdraw and close are refused, 0 = no lock
+    pub unlock_at: i64,
+    // Multisig members, empty while the vault is single-creator
+    #[max_len(MAX_SIGNERS)]
+    pub signers: Vec<Pubkey>,
+    // Approvals a withdraw proposal needs, 0 while the vault is single-creator
+    pub threshold: u8,
+    // Id for the next proposal, part of the proposal seeds
+    pub proposal_count: u64,
+    // Most lamports that may leave the vault per fixed CAP_WINDOW, 0 = no cap
+    pub withdraw_cap: u64,
+    // When the current window opened and what has left the vault in it
+    pub window_start: i64,
+    pub window_spent: u64,
+    // A raised cap waiting out CAP_RAISE_DELAY, takes over once `pending_cap_at` passes (0 = none)
+    pub pending_cap: u64,
+    pub pending_cap_at: i64,
+    // Keys that can recover the vault if the owner key is lost, empty = no recovery
+    #[max_len(MAX_GUARDIANS)]
+    pub guardians: Vec<Pubkey>,
+    pub guardian_threshold: u8,
+    // Lamports moved from the vault into its stake account, rewards not included
+    pub staked: u64,
+    // Lifetime SOL in and out, rent and staking excluded, so that
+    // vault lamports = rent + total_deposited - total_withdrawn - staked
+    pub total_deposited: u64,
+    pub total_withdrawn: u64,
+    pub last_activity_slot: u64,
+}
+impl VaultState {
+    pub fn check_owner(&self, key: &Pubkey) -> Result<()> {
+        require_keys_eq!(*key, self.owner, VaultError::Unauthorized);
+        Ok(())
+    }
+
+    pub fn set_owner(&mut self, owner: Pubkey) {
+        self.owner = owner;
+        self.pending_owner = None;
+        self.owner_nonce += 1;
+    }
+
+    // Lowering applies at once; raising (or removing) the cap waits CAP_RAISE_DELAY
+    pub fn set_withdraw_cap(&mut self, cap: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        self.apply_pending_cap(now);
+        let lowers = self.withdraw_cap == 0 || (cap != 0 && cap <= self.withdraw_cap);
+        if lowers {
+            self.withdraw_cap = cap;
+            self.pending_cap = 0;
+            self.pending_cap_at = 0;
+        } else {
+            self.pending_cap = cap;
+            self.pending_cap_at = now + CAP_RAISE_DELAY;
+        }
+        Ok(())
+    }
+
+    // Records `amount` leaving the vault, failing once it would take the current window over the cap
+    pub fn spend(&mut self, amount: u64) -> Result<()> {
+        let clock = Clock::get()?;
+        let now = clock.unix_timestamp;
+        self.apply_pending_cap(now);
+        if self.withdraw_cap != 0 {
+            if now >= self.window_start + CAP_WINDOW {
+                self.window_start = now;
+                self.window_spent = 0;
+            }
+            let spent = self
+                .window_spent
+                .checked_add(amount)
+                .ok_or(VaultError::WithdrawCapExceeded)?;
+            if spent > self.withdraw_cap {
+                msg!(
+                    "{} lamports left in this window",
+                    self.withdraw_cap.saturating_sub(self.window_spent)
+                );
+                return err!(VaultError::WithdrawCapExceeded);
+            }
+            self.window_spent = spent;
+        }
+        self.total_withdrawn = self.total_withdrawn.saturating_add(amount);
+        self.last_activity_slot = clock.slot;
+        Ok(())
+    }
+
+    // Records `amount` entering the vault
+    pub fn record_deposit(&mut self, amount: u64) -> Result<()> {
+        self.total_deposited = self.total_deposited.saturating_add(amount);
+        self.last_activity_slot = Clock::get()?.slot;
+        Ok(())
+    }
+
+    fn apply_pending_cap(&mut self, now: i64) {
+        if self.pending_cap_at != 0 && now >= self.pending_cap_at {
+            self.withdraw_cap = self.pending_cap;
+            self.pending_cap = 0;
+            self.pending_cap_at = 0;
+        }
+    }
+
+    pub fn check_guardian(&self, key: &Pubkey) -> Result<()> {
+        require!(self.guardians.contains(key), VaultError::Unauthorized);
+        Ok(())
+    }
+
+    pub fn is_multisig(&self) -> bool {
+        self.threshold > 0
+    }
+
+    // Multisig vaults only move funds through proposals, so the owner-only paths that move
+    // funds, or lock and cap them, refuse them
+    pub fn check_single_signer(&self) -> Result<()> {
+        require!(!self.is_multisig(), VaultError::MultisigRequired);
+        Ok(())
+    }
+
+    pub fn check_member(&self, key: &Pubkey) -> Result<()> {
+        require!(
+            self.is_multisig() && self.signers.contains(key),
+            VaultError::Unauthorized
+        );
+        Ok(())
+    }
+
+    // Fails with `VaultLocked`, logging the seconds left, until `unlock_at` has passed
+    pub fn check_unlocked(&self) -> Result<()> {
+        let remaining = self.unlock_at - Clock::get()?.unix_timestamp;
+        if remaining > 0 {
+            msg!("Vault unlocks in {} seconds", remaining);
+            return err!(VaultError::VaultLocked);
+        }
+        Ok(())
+    }
+}
+
+// Unique keys, at most `max`, with 1 <= threshold <= keys
+pub fn is_valid_key_set(keys: &[Pubkey], threshold: u8, max: usize) -> bool {
+    keys.len() <= max
+        && threshold > 0
+        && usize::from(threshold) <= keys.len()
+        && keys
+            .iter()
+            .enumerate()
+            .all(|(i, key)| !keys[..i].contains(key))
+}
+
+// Lamports the vault can pay out while staying at its rent-exempt minimum
+pub fn withdrawable_lamports(vault: &AccountInfo) -> Result<u64> {
+    let rent_exempt = Rent::get()?.minimum_balance(vault.data_len());
+    Ok(vault.lamports().saturating_sub(rent_exempt))
+}
+
+// Token-2022 mint with a transfer fee extension, whose fees withheld in a
+// recipient ATA would keep that ATA from being closed
+fn has_transfer_fee(mint: &InterfaceAccount<Mint>) -> Result<bool> {
+    let info = mint.to_account_info();
+    if *info.owner != spl_token_2022::ID {
+        return Ok(false);
+    }
+    let data = info.try_borrow_data()?;
+    let state = StateWithExtensions::<MintState>::unpack(&data)?;
+    Ok(state.get_extension::<TransferFeeConfig>().is_ok())
+}
+
+// The token vault moves tokens with a plain `transfer_checked`: fees withheld
+// in the vault ATA would keep `close_token_vault` from closing it, and a hook
+// program would never get its extra accounts
+pub fn check_vault_mint(mint: &InterfaceAccount<Mint>) -> Result<()> {
+    require!(!has_transfer_fee(mint)?, VaultError::TransferFeeMint);
+    let info = mint.to_account_info();
+    if *info.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+    let data = info.try_borrow_data()?;
+    let state = StateWithExtensions::<MintState>::unpack(&data)?;
+    require!(
+        transfer_hook::get_program_id(&state).is_none(),
+        VaultError::TransferHookMint
+    );
+    Ok(())
+}
+
+// Withdraw allowance the creator grants a delegate on one vault
+#[derive(InitSpace)]
+#[account]
+pub struct DelegateAllowance {
+    pub vault_state: Pubkey,
+    pub delegate: Pubkey,
+    // Lamports the delegate may withdraw, in total or per epoch
+    pub allowance: u64,
+    // When set, `spent` starts again from 0 every epoch
+    pub per_epoch: bool,
+    pub spent: u64,
+    // Epoch `spent` was counted in, only read when `per_epoch` is set
+    pub epoch: u64,
+    // `owner_nonce` of the vault when approved; the allowance lapses once ownership moves
+    pub owner_nonce: u64,
+    pub bump: u8,
+}
+impl DelegateAllowance {
+    // Counts `amount` against the allowance, failing once it would go over the cap
+    pub fn spend(&mut self, amount: u64) -> Result<()> {
+        let epoch = Clock::get()?.epoch;
+        if self.per_epoch && self.epoch != epoch {
+            self.epoch = epoch;
+            self.spent = 0;
+        }
+        let spent = self
+            .spent
+            .checked_add(amount)
+            .ok_or(VaultError::AllowanceExceeded)?;
+        require!(spent <= self.allowance, VaultError::AllowanceExceeded);
+        self.spent = spent;
+        Ok(())
+    }
+}
+
+// A multisig withdraw waiting for approvals, closed once executed or cancelled
+#[derive(InitSpace)]
+#[account]
+pub struct WithdrawProposal {
+    pub vault_state: Pubkey,
+    pub proposer: Pubkey,
+    pub id: u64,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    // Token vault the proposal draws from, None for SOL from the vault PDA
+    pub mint: Option<Pubkey>,
+    // Members that approved so far, the proposer included
+    #[max_len(MAX_SIGNERS)]
+    pub approvals: Vec<Pubkey>,
+    // Past this the proposal is stale: it can no longer be approved or executed, only cancelled
+    pub expires_at: i64,
+    pub bump: u8,
+}
+impl WithdrawProposal {
+    pub fn is_stale(&self) -> Result<bool> {
+        Ok(Clock::get()?.unix_timestamp >= self.expires_at)
+    }
+}
+
+// A guardian-started handover to `new_owner`, which the owner can veto until `executable_at`
+#[derive(InitSpace)]
+#[account]
+pub struct RecoveryRequest {
+    pub vault_state: Pubkey,
+    pub initiator: Pubkey,
+    pub new_owner: Pubkey,
+    // Guardians that approved so far, the initiator included
+    #[max_len(MAX_GUARDIANS)]
+    pub approvals: Vec<Pubkey>,
+    // 0 until the guardian threshold is reached, then RECOVERY_DELAY after that
+    pub executable_at: i64,
+    // Any guardian can cancel the request if it is still short of the threshold by then
+    pub expires_at: i64,
+    pub bump: u8,
+}
+impl RecoveryRequest {
+    // Guardians the owner has since removed no longer count
+    pub fn threshold_met(&self, vault_state: &VaultState) -> bool {
+        let approvals = self
+            .approvals
+            .iter()
+            .filter(|key| vault_state.guardians.contains(key))
+            .count();
+        vault_state.guardian_threshold > 0
+            && approvals >= usize::from(vault_state.guardian_threshold)
+    }
+
+    // The owner's veto window only opens once enough guardians stand behind the request
+    pub fn start_timelock(&mut self, vault_state: &VaultState) -> Result<()> {
+        if self.executable_at == 0 && self.threshold_met(vault_state) {
+            self.executable_at = Clock::get()?.unix_timestamp + RECOVERY_DELAY;
+        }
+        Ok(())
+    }
+
+    pub fn is_stale(&self) -> Result<bool> {
+        Ok(self.executable_at == 0 && Clock::get()?.unix_timestamp >= self.expires_at)
+    }
+}
+
+// Pays `recipient` `amount_per_period` for every full `period` between `start_at` and `end_at`.
+// `end_at - start_at` is always a whole number of periods.
+#[derive(InitSpace)]
+#[account]
+pub struct PayoutSchedule {
+    pub vault_state: Pubkey,
+    pub recipient: Pubkey,
+    pub amount_per_period: u64,
+    // Seconds per period
+    pub period: i64,
+    pub start_at: i64,
+    pub end_at: i64,
+    pub paid_periods: u64,
+    pub bump: u8,
+}
+impl PayoutSchedule {
+    // Full periods that have elapsed by `now` but are not paid yet
+    pub fn due_periods(&self, now: i64) -> u64 {
+        let elapsed = now.min(self.end_at) - self.start_at;
+        ((elapsed / self.period) as u64).saturating_sub(self.paid_periods)
+    }
+
+    // What the recipient is owed if the schedule stops at `now`: every due period,
+    // plus the elapsed share of the period in progress if the schedule hasn't ended.
+    // Never more than running the schedule to the end would pay.
+    pub fn owed_on_cancel(&self, now: i64) -> Result<u64> {
+        let due = self
+            .due_periods(now)
+            .checked_mul(self.amount_per_period)
+            .ok_or(VaultError::InvalidAmount)?;
+        if now >= self.end_at {
+            return Ok(due);
+        }
+        let into_period = (now - self.start_at) % self.period;
+        let partial = into_period as u128 * self.amount_per_period as u128 / self.period as u128;
+        Ok(due
+            .checked_add(partial as u64)
+            .ok_or(VaultError::InvalidAmount)?)
+    }
+}
+
+// Terms of a vesting grant, as passed to `create_vesting` and `create_token_vesting`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct VestingTerms {
+    pub amount: u64,
+    pub start_at: i64,
+    // Nothing can be claimed before this, after it the linear schedule applies in full
+    pub cliff_at: i64,
+    // Seconds from `start_at` until everything has vested
+    pub duration: i64,
+    // Whether the owner may later take back the unvested part
+    pub revocable: bool,
+}
+impl VestingTerms {
+    pub fn validate(&self) -> Result<()> {
+        let end_at = self
+            .start_at
+            .checked_add(self.duration)
+            .ok_or(VaultError::InvalidVesting)?;
+        require!(
+            self.amount > 0
+                && self.duration > 0
+                && self.start_at <= self.cliff_at
+                && self.cliff_at <= end_at,
+            VaultError::InvalidVesting
+        );
+        Ok(())
+    }
+}
+
+// Funds set aside from a vault for `beneficiary`, vesting linearly from `start_at`.
+// SOL grants hold the lamports in this account, token grants in its ATA for `mint`.
+// Token grants add the mint to the seeds, so a beneficiary can hold one SOL grant
+// and one grant per mint from the same vault.
+#[derive(InitSpace)]
+#[account]
+pub struct VestingGrant {
+    pub vault_state: Pubkey,
+    // Owner that created the grant, gets the rent back when it closes
+    pub funder: Pubkey,
+    pub beneficiary: Pubkey,
+    pub mint: Option<Pubkey>,
+    pub total_amount: u64,
+    pub claimed: u64,
+    pub start_at: i64,
+    pub cliff_at: i64,
+    pub duration: i64,
+    pub revocable: bool,
+    pub bump: u8,
+}
+impl VestingGrant {
+    pub fn vested(&self, now: i64) -> u64 {
+        if now < self.cliff_at {
+            return 0;
+        }
+        let elapsed = (now - self.start_at).min(self.duration);
+        (self.total_amount as u128 * elapsed as u128 / self.duration as u128) as u64
+    }
+
+    // Vested but not yet claimed
+    pub fn claimable(&self, now: i64) -> u64 {
+        self.vested(now) - self.claimed
+    }
+}
+
+// Layout of `VaultState` before vaults had an id, kept so `migrate_vault` can read it
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct VaultStateV0 {
+    pub creator: Pubkey,
+    pub state_bump: u8,
+    pub vault_bump: u8,
+}
+impl VaultStateV0 {
+    pub const LEN: usize = 32 + 1 + 1;
 }
@@ -0,0 +1,179 @@
+//! `migrate_vault` needs a vault in the pre-`vault_id` layout, which no current
+//! instruction can create, so it is tested here with the legacy accounts
+//! written straight into an in-process bank rather than from the TS suite.
+
+use anchor_lang::{
+    prelude::*, solana_program::entrypoint::ProgramResult,
+    solana_program::instruction::Instruction, system_program, Discriminator, InstructionData,
+};
+use simple_vault::{
+    constants::{VAULT_SEED, VAULT_STATE_SEED},
+    state::VaultState,
+    ID as PROGRAM_ID,
+};
+use solana_account::Account;
+use solana_keypair::Keypair;
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+// `processor!` takes a plain entrypoint, while Anchor's `entry` ties the account
+// slice to the accounts' own lifetime; a leaked copy of the slice bridges the two
+fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(accounts.to_vec().into_boxed_slice());
+    simple_vault::entry(program_id, accounts, data)
+}
+
+struct Legacy {
+    owner: Keypair,
+    vault_state: Pubkey,
+    vault: Pubkey,
+}
+
+// Bank holding a legacy vault_state for a fresh owner, with `vault_lamports` in its vault
+async fn setup(vault_lamports: u64) -> (ProgramTestContext, Legacy) {
+    let mut program_test = ProgramTest::default();
+    program_test.prefer_bpf(false);
+    program_test.add_program("simple_vault", PROGRAM_ID, processor!(process));
+
+    let owner = Keypair::new();
+    let (vault_state, state_bump) =
+        Pubkey::find_program_address(&[VAULT_STATE_SEED, owner.pubkey().as_ref()], &PROGRAM_ID);
+    let (vault, vault_bump) = Pubkey::find_program_address(
+        &[VAULT_SEED, owner.pubkey().as_ref(), vault_state.as_ref()],
+        &PROGRAM_ID,
+    );
+
+    // Discriminator, creator, state_bump, vault_bump
+    let mut data = VaultState::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(owner.pubkey().as_ref());
+    data.extend_from_slice(&[state_bump, vault_bump]);
+    let rent = Rent::default();
+    program_test.add_account(
+        vault_state,
+        Account {
+            lamports: rent.minimum_balance(data.len()),
+            data,
+            owner: PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    program_test.add_account(
+        owner.pubkey(),
+        Account::new(10 * LAMPORTS_PER_SOL, 0, &system_program::ID),
+    );
+    if vault_lamports > 0 {
+        program_test.add_account(vault, Account::new(vault_lamports, 0, &system_program::ID));
+    }
+
+    let context = program_test.start_with_context().await;
+    (
+        context,
+        Legacy {
+            owner,
+            vault_state,
+            vault,
+        },
+    )
+}
+
+fn new_addresses(owner: &Pubkey, vault_id: u64) -> (Pubkey, Pubkey) {
+    let (vault_state, _) = Pubkey::find_program_address(
+        &[VAULT_STATE_SEED, owner.as_ref(), &vault_id.to_le_bytes()],
+        &PROGRAM_ID,
+    );
+    let (vault, _) = Pubkey::find_program_address(
+        &[VAULT_SEED, owner.as_ref(), vault_state.as_ref()],
+        &PROGRAM_ID,
+    );
+    (vault_state, vault)
+}
+
+async fn migrate(context: &mut ProgramTestContext, legacy: &Legacy, vault_id: u64) {
+    let (vault_state, vault) = new_addresses(&legacy.owner.pubkey(), vault_id);
+    let ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: simple_vault::accounts::MigrateVaultAccounts {
+            signer: legacy.owner.pubkey(),
+            legacy_vault_state: legacy.vault_state,
+            legacy_vault: legacy.vault,
+            vault_state,
+            vault,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: simple_vault::instruction::MigrateVault { vault_id }.data(),
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&legacy.owner.pubkey()),
+        &[&legacy.owner],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+}
+
+async fn lamports(context: &mut ProgramTestContext, key: &Pubkey) -> u64 {
+    context.banks_client.get_balance(*key).await.unwrap()
+}
+
+async fn vault_rent(context: &mut ProgramTestContext) -> u64 {
+    context
+        .banks_client
+        .get_rent()
+        .await
+        .unwrap()
+        .minimum_balance(0)
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn migrate_moves_the_balance_and_closes_the_legacy_state() {
+    let balance = 2 * LAMPORTS_PER_SOL;
+    let (mut context, legacy) = setup(balance).await;
+    migrate(&mut context, &legacy, 7).await;
+
+    let (vault_state, vault) = new_addresses(&legacy.owner.pubkey(), 7);
+    assert_eq!(lamports(&mut context, &vault).await, balance);
+    assert_eq!(lamports(&mut context, &legacy.vault).await, 0);
+    assert!(context
+        .banks_client
+        .get_account(legacy.vault_state)
+        .await
+        .unwrap()
+        .is_none());
+
+    let account = context
+        .banks_client
+        .get_account(vault_state)
+        .await
+        .unwrap()
+        .unwrap();
+    let state = VaultState::try_deserialize(&mut &account.data[..]).unwrap();
+    assert_eq!(state.creator, legacy.owner.pubkey());
+    assert_eq!(state.owner, legacy.owner.pubkey());
+    assert_eq!(state.vault_id, 7);
+    let rent = vault_rent(&mut context).await;
+    assert_eq!(state.total_deposited, balance - rent);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn migrate_tops_an_empty_vault_up_to_rent() {
+    let (mut context, legacy) = setup(0).await;
+    migrate(&mut context, &legacy, 0).await;
+
+    let (vault_state, vault) = new_addresses(&legacy.owner.pubkey(), 0);
+    let rent = vault_rent(&mut context).await;
+    assert_eq!(lamports(&mut context, &vault).await, rent);
+
+    let account = context
+        .banks_client
+        .get_account(vault_state)
+        .await
+        .unwrap()
+        .unwrap();
+    let state = VaultState::try_deserialize(&mut &account.data[..]).unwrap();
+    assert_eq!(state.total_deposited, 0);
+}
@@ -1,6 +1,19 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { SimpleVault } from "../target/types/simple_vault";
+import {
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  createMint,
+  ExtensionType,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getMintLen,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
 import { expect } from "chai";
 const SYSTEM_PROGRAM = anchor.web3.SystemProgram.programId;
 const LAMPORTS_PER_SOL = anchor.web3.LAMPORTS_PER_SOL;
@@ -32,13 +45,19 @@ describe("simple-vault", () => {
   const program = anchor.workspace.simpleVault as Program<SimpleVault>;
   const creator = provider.wallet.publicKey;
   const user = anchor.web3.Keypair.generate();
+  const vaultId = new anchor.BN(0);
 
   // Derive PDAs
-  const [vaultStatePda, stateBump] =
+  const findVaultState = (owner: anchor.web3.PublicKey, id: anchor.BN) =>
     anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("vault_state"), creator.toBuffer()],
+      [
+        Buffer.from("vault_state"),
+        owner.toBuffer(),
+        id.toArrayLike(Buffer, "le", 8),
+      ],
       program.programId
     );
+  const [vaultStatePda, stateBump] = findVaultState(creator, vaultId);
 
   const [vaultPda, vaultBump] = anchor.web3.PublicKey.findProgramAddressSync(
     [Buffer.from("vault"), creator.toBuffer(), vaultStatePda.toBuffer()],
@@ -67,7 +86,7 @@ describe("simple-vault", () => {
     );
 
     const tx = await program.methods
-      .initializeVault()
+      .initializeVault(vaultId, new anchor.BN(0))
       .accounts({
         signer: creator,
         vaultState: vaultStatePda,
@@ -91,6 +110,7 @@ describe("simple-vault", () => {
     expect(vaultStateAccount.vaultBump).to.equal(vaultBump);
     expect(vaultStateAccount.stateBump).to.equal(stateBump);
     expect(vaultStateAccount.creator.toBase58()).to.equal(creator.toBase58());
+    expect(vaultStateAccount.vaultId.toNumber()).to.equal(0);
     expect(finalVaultBalance).to.equal(rentExempt);
   });
 
@@ -194,6 +214,47 @@ describe("simple-vault", () => {
     expect(finalUserBalance).to.equal(initialUserBalance + amount - 5000);
   });
 
+  it("Events and counters reconcile the vault from one account read", async () => {
+    logSection("TEST: Events and Counters");
+
+    const amount = 0.1 * LAMPORTS_PER_SOL;
+    const tx = await program.methods
+      .deposit(new anchor.BN(amount))
+      .accounts({
+        signer: creator,
+        vaultState: vaultStatePda,
+        vault: vaultPda,
+        systemProgram: SYSTEM_PROGRAM,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    const txInfo = await provider.connection.getTransaction(tx, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const parser = new anchor.EventParser(
+      program.programId,
+      new anchor.BorshCoder(program.idl)
+    );
+    const [event] = [...parser.parseLogs(txInfo.meta.logMessages)];
+    expect(event.name).to.equal("vaultDeposited");
+    expect(event.data.amount.toNumber()).to.equal(amount);
+
+    const state = await program.account.vaultState.fetch(vaultStatePda);
+    expect(event.data.totalDeposited.toNumber()).to.equal(
+      state.totalDeposited.toNumber()
+    );
+    expect(state.lastActivitySlot.toNumber()).to.equal(txInfo.slot);
+
+    const rentExempt =
+      await provider.connection.getMinimumBalanceForRentExemption(0);
+    expect(await provider.connection.getBalance(vaultPda)).to.equal(
+      rentExempt +
+        state.totalDeposited.toNumber() -
+        state.totalWithdrawn.toNumber()
+    );
+  });
+
   it("Withdraw 2 SOL from the vault! Should Fail (Insufficient Funds)", async () => {
     logSection("TEST: Withdraw 2 SOL (Should Fail)");
 
@@ -270,6 +331,1495 @@ describe("simple-vault", () => {
     expect(finalUserBalance).to.equal(initialUserBalance);
   });
 
+  it("Anyone can deposit into the creator's vault with deposit_for", async () => {
+    logSection("TEST: Deposit For");
+
+    const amount = 0.25 * LAMPORTS_PER_SOL;
+    const initialVaultBalance = await provider.connection.getBalance(vaultPda);
+
+    const tx = await program.methods
+      .depositFor(creator, new anchor.BN(amount))
+      .accountsStrict({
+        depositor: user.publicKey,
+        vaultState: vaultStatePda,
+        vault: vaultPda,
+        systemProgram: SYSTEM_PROGRAM,
+      })
+      .signers([user])
+      .rpc({ commitment: "confirmed" });
+    logTransactionResult("Deposit for creator successful", tx);
+
+    expect(await provider.connection.getBalance(vaultPda)).to.equal(
+      initialVaultBalance + amount
+    );
+
+    const txInfo = await provider.connection.getTransaction(tx, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const parser = new anchor.EventParser(
+      program.programId,
+      new anchor.BorshCoder(program.idl)
+    );
+    const events = [...parser.parseLogs(txInfo.meta.logMessages)];
+    expect(events).to.have.length(1);
+    expect(events[0].name).to.equal("vaultDepositedFor");
+    expect(events[0].data.depositor.toBase58()).to.equal(
+      user.publicKey.toBase58()
+    );
+    expect(events[0].data.creator.toBase58()).to.equal(creator.toBase58());
+    expect(events[0].data.amount.toNumber()).to.equal(amount);
+    const state = await program.account.vaultState.fetch(vaultStatePda);
+    expect(events[0].data.totalDeposited.toNumber()).to.equal(
+      state.totalDeposited.toNumber()
+    );
+  });
+
+  it("A delegate can withdraw up to its allowance, then is revoked", async () => {
+    logSection("TEST: Delegate Allowance");
+
+    const [allowancePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("delegate"),
+        vaultStatePda.toBuffer(),
+        user.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const allowance = 0.1 * LAMPORTS_PER_SOL;
+    await program.methods
+      .approveDelegate(user.publicKey, new anchor.BN(allowance), false)
+      .accountsStrict({
+        signer: creator,
+        vaultState: vaultStatePda,
+        delegateAllowance: allowancePda,
+        systemProgram: SYSTEM_PROGRAM,
+      })
+      .rpc();
+
+    const withdrawAccounts = {
+      delegate: user.publicKey,
+      vaultState: vaultStatePda,
+      delegateAllowance: allowancePda,
+      vault: vaultPda,
+      systemProgram: SYSTEM_PROGRAM,
+    };
+    const initialVaultBalance = await provider.connection.getBalance(vaultPda);
+    const tx = await program.methods
+      .delegateWithdraw(new anchor.BN(0.06 * LAMPORTS_PER_SOL))
+      .accountsStrict(withdrawAccounts)
+      .signers([user])
+      .rpc({ commitment: "confirmed" });
+    expect(await provider.connection.getBalance(vaultPda)).to.equal(
+      initialVaultBalance - 0.06 * LAMPORTS_PER_SOL
+    );
+
+    // Delegate withdrawals show up as VaultWithdrawn like the owner's own
+    const txInfo = await provider.connection.getTransaction(tx, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const parser = new anchor.EventParser(
+      program.programId,
+      new anchor.BorshCoder(program.idl)
+    );
+    const events = [...parser.parseLogs(txInfo.meta.logMessages)];
+    expect(events.map((event) => event.name)).to.deep.equal([
+      "vaultWithdrawn",
+    ]);
+    expect(events[0].data.recipient.toBase58()).to.equal(
+      user.publicKey.toBase58()
+    );
+    expect(events[0].data.amount.toNumber()).to.equal(
+      0.06 * LAMPORTS_PER_SOL
+    );
+    const allowanceAccount = await program.account.delegateAllowance.fetch(
+      allowancePda
+    );
+    expect(allowanceAccount.spent.toNumber()).to.equal(
+      0.06 * LAMPORTS_PER_SOL
+    );
+
+    try {
+      await program.methods
+        .delegateWithdraw(new anchor.BN(0.06 * LAMPORTS_PER_SOL))
+        .accountsStrict(withdrawAccounts)
+        .signers([user])
+        .rpc();
+      expect.fail("Withdrawing past the allowance should have failed.");
+    } catch (err) {
+      expect(err.toString()).to.include("AllowanceExceeded");
+    }
+
+    await program.methods
+      .revokeDelegate()
+      .accountsStrict({
+        signer: creator,
+        vaultState: vaultStatePda,
+        delegateAllowance: allowancePda,
+      })
+      .rpc();
+    expect(await provider.connection.getAccountInfo(allowancePda)).to.be.null;
+  });
+
+  it("A second vault_id gives the creator an independent vault", async () => {
+    logSection("TEST: Second Vault");
+
+    const savingsId = new anchor.BN(7);
+    const [savingsStatePda] = findVaultState(creator, savingsId);
+    const [savingsVaultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("vault"),
+        creator.toBuffer(),
+        savingsStatePda.toBuffer(),
+      ],
+      program.programId
+    );
+    const accounts = {
+      signer: creator,
+      vaultState: savingsStatePda,
+      vault: savingsVaultPda,
+      systemProgram: SYSTEM_PROGRAM,
+    };
+    const mainVaultBalance = await provider.connection.getBalance(vaultPda);
+
+    await program.methods
+      .initializeVault(savingsId, new anchor.BN(0))
+      .accounts(accounts)
+      .rpc();
+    const rentExempt =
+      await provider.connection.getMinimumBalanceForRentExemption(0);
+    await program.methods
+      .deposit(new anchor.BN(0.2 * LAMPORTS_PER_SOL))
+      .accounts(accounts)
+      .rpc();
+
+    expect(await provider.connection.getBalance(savingsVaultPda)).to.equal(
+      rentExempt + 0.2 * LAMPORTS_PER_SOL
+    );
+    expect(await provider.connection.getBalance(vaultPda)).to.equal(
+      mainVaultBalance
+    );
+
+    // Only what sits above the rent floor can be withdrawn
+    try {
+      await program.methods
+        .withdraw(new anchor.BN(rentExempt + 0.2 * LAMPORTS_PER_SOL))
+        .accounts(accounts)
+        .rpc();
+      expect.fail("Withdrawing into the rent floor should have failed.");
+    } catch (err) {
+      expect(err.toString()).to.include("InsufficientFunds");
+    }
+    await program.methods.withdrawAll().accounts(accounts).rpc();
+    expect(await provider.connection.getBalance(savingsVaultPda)).to.equal(
+      rentExempt
+    );
+
+    await program.methods.close().accountsStrict(accounts).rpc();
+    expect(await provider.connection.getAccountInfo(savingsStatePda)).to.be
+      .null;
+    expect(await provider.connection.getBalance(savingsVaultPda)).to.equal(0);
+  });
+
+  it("Withdraw cap limits outflow, raises wait and cuts apply at once", async () => {
+    logSection("TEST: Withdraw Cap");
+
+    const hotId = new anchor.BN(3);
+    const [hotStatePda] = findVaultState(creator, hotId);
+    const [hotVaultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), creator.toBuffer(), hotStatePda.toBuffer()],
+      program.programId
+    );
+    const hot = {
+      signer: creator,
+      vaultState: hotStatePda,
+      vault: hotVaultPda,
+      systemProgram: SYSTEM_PROGRAM,
+    };
+    const setCap = (cap: number) =>
+      program.methods
+        .setWithdrawCap(new anchor.BN(cap))
+        .accountsStrict({ signer: creator, vaultState: hotStatePda })
+        .rpc();
+    const withdraw = (amount: number) =>
+      program.methods.withdraw(new anchor.BN(amount)).accounts(hot).rpc();
+
+    await program.methods
+      .initializeVault(hotId, new anchor.BN(0))
+      .accounts(hot)
+      .rpc();
+    await program.methods
+      .deposit(new anchor.BN(1 * LAMPORTS_PER_SOL))
+      .accounts(hot)
+      .rpc();
+    await setCap(0.3 * LAMPORTS_PER_SOL);
+
+    await withdraw(0.2 * LAMPORTS_PER_SOL);
+    try {
+      await withdraw(0.2 * LAMPORTS_PER_SOL);
+      expect.fail("Withdrawing past the cap should have failed.");
+    } catch (err) {
+      expect(err.toString()).to.include("WithdrawCapExceeded");
+    }
+
+    // Raising only queues the new cap
+    await setCap(0.5 * LAMPORTS_PER_SOL);
+    let state = await program.account.vaultState.fetch(hotStatePda);
+    expect(state.withdrawCap.toNumber()).to.equal(0.3 * LAMPORTS_PER_SOL);
+    expect(state.pendingCap.toNumber()).to.equal(0.5 * LAMPORTS_PER_SOL);
+    expect(state.pendingCapAt.toNumber()).to.be.greaterThan(0);
+
+    // Lowering applies immediately and drops the queued raise
+    await setCap(0.25 * LAMPORTS_PER_SOL);
+    state = await program.account.vaultState.fetch(hotStatePda);
+    expect(state.withdrawCap.toNumber()).to.equal(0.25 * LAMPORTS_PER_SOL);
+    expect(state.pendingCapAt.toNumber()).to.equal(0);
+    expect(state.windowSpent.toNumber()).to.equal(0.2 * LAMPORTS_PER_SOL);
+  });
+
+  it("Ownership moves in two steps and the PDAs stay put", async () => {
+    logSection("TEST: Ownership Transfer");
+
+    const handoverId = new anchor.BN(4);
+    const [handoverStatePda] = findVaultState(creator, handoverId);
+    const [handoverVaultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("vault"),
+        creator.toBuffer(),
+        handoverStatePda.toBuffer(),
+      ],
+      program.programId
+    );
+    const accountsFor = (signer: anchor.web3.PublicKey) => ({
+      signer,
+      vaultState: handoverStatePda,
+      vault: handoverVaultPda,
+      systemProgram: SYSTEM_PROGRAM,
+    });
+
+    await program.methods
+      .initializeVault(handoverId, new anchor.BN(0))
+      .accounts(accountsFor(creator))
+      .rpc();
+    await program.methods
+      .deposit(new anchor.BN(0.3 * LAMPORTS_PER_SOL))
+      .accounts(accountsFor(creator))
+      .rpc();
+
+    // A delegate the old owner approved loses its allowance with the handover
+    const helper = anchor.web3.Keypair.generate();
+    const [helperAllowancePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("delegate"),
+        handoverStatePda.toBuffer(),
+        helper.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    await program.methods
+      .approveDelegate(
+        helper.publicKey,
+        new anchor.BN(0.1 * LAMPORTS_PER_SOL),
+        false
+      )
+      .accountsStrict({
+        signer: creator,
+        vaultState: handoverStatePda,
+        delegateAllowance: helperAllowancePda,
+        systemProgram: SYSTEM_PROGRAM,
+      })
+      .rpc();
+
+    await program.methods
+      .proposeNewOwner(user.publicKey)
+      .accountsStrict({ signer: creator, vaultState: handoverStatePda })
+      .rpc();
+    // The old owner keeps control until the new one accepts
+    let state = await program.account.vaultState.fetch(handoverStatePda);
+    expect(state.owner.toBase58()).to.equal(creator.toBase58());
+    expect(state.pendingOwner.toBase58()).to.equal(user.publicKey.toBase58());
+
+    await program.methods
+      .acceptOwnership()
+      .accountsStrict({ signer: user.publicKey, vaultState: handoverStatePda })
+      .signers([user])
+      .rpc();
+    state = await program.account.vaultState.fetch(handoverStatePda);
+    expect(state.owner.toBase58()).to.equal(user.publicKey.toBase58());
+    expect(state.creator.toBase58()).to.equal(creator.toBase58());
+    expect(state.pendingOwner).to.be.null;
+    expect(state.ownerNonce.toNumber()).to.equal(1);
+
+    try {
+      await program.methods
+        .withdraw(new anchor.BN(0.1 * LAMPORTS_PER_SOL))
+        .accounts(accountsFor(creator))
+        .rpc();
+      expect.fail("The previous owner should no longer withdraw.");
+    } catch (err) {
+      expect(err.toString()).to.include("Unauthorized");
+    }
+    try {
+      await program.methods
+        .delegateWithdraw(new anchor.BN(0.05 * LAMPORTS_PER_SOL))
+        .accountsStrict({
+          delegate: helper.publicKey,
+          vaultState: handoverStatePda,
+          delegateAllowance: helperAllowancePda,
+          vault: handoverVaultPda,
+          systemProgram: SYSTEM_PROGRAM,
+        })
+        .signers([helper])
+        .rpc();
+      expect.fail("The old owner's delegate should no longer withdraw.");
+    } catch (err) {
+      expect(err.toString()).to.include("StaleAllowance");
+    }
+
+    const vaultBalance = await provider.connection.getBalance(
+      handoverVaultPda
+    );
+    await program.methods
+      .withdraw(new anchor.BN(0.1 * LAMPORTS_PER_SOL))
+      .accounts(accountsFor(user.publicKey))
+      .signers([user])
+      .rpc();
+    expect(await provider.connection.getBalance(handoverVaultPda)).to.equal(
+      vaultBalance - 0.1 * LAMPORTS_PER_SOL
+    );
+  });
+
+  it("Guardians start a recovery that the owner can veto", async () => {
+    logSection("TEST: Guardian Recovery");
+
+    const guardedId = new anchor.BN(5);
+    const [guardedStatePda] = findVaultState(creator, guardedId);
+    const [guardedVaultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("vault"),
+        creator.toBuffer(),
+        guardedStatePda.toBuffer(),
+      ],
+      program.programId
+    );
+    const [recoveryPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("recovery"), guardedStatePda.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .initializeVault(guardedId, new anchor.BN(0))
+      .accounts({
+        signer: creator,
+        vaultState: guardedStatePda,
+        vault: guardedVaultPda,
+        systemProgram: SYSTEM_PROGRAM,
+      })
+      .rpc();
+    const guardian = anchor.web3.Keypair.generate();
+    await program.methods
+      .setGuardians([user.publicKey, guardian.publicKey], 2)
+      .accountsStrict({ signer: creator, vaultState: guardedStatePda })
+      .rpc();
+
+    await program.methods
+      .startRecovery(user.publicKey)
+      .accountsStrict({
+        guardian: user.publicKey,
+        vaultState: guardedStatePda,
+        recovery: recoveryPda,
+        systemProgram: SYSTEM_PROGRAM,
+      })
+      .signers([user])
+      .rpc();
+
+    const recoveryAccounts = {
+      vaultState: guardedStatePda,
+      recovery: recoveryPda,
+      initiator: user.publicKey,
+    };
+    // The veto window only opens once the threshold is reached
+    let recovery = await program.account.recoveryRequest.fetch(recoveryPda);
+    expect(recovery.executableAt.toNumber()).to.equal(0);
+    try {
+      await program.methods
+        .completeRecovery()
+        .accountsStrict(recoveryAccounts)
+        .rpc();
+      expect.fail("Completing below the threshold should have failed.");
+    } catch (err) {
+      expect(err.toString()).to.include("ThresholdNotMet");
+    }
+    // Other guardians can only cancel once the request has expired
+    try {
+      await program.methods
+        .cancelRecovery()
+        .accountsStrict({ signer: guardian.publicKey, ...recoveryAccounts })
+        .signers([guardian])
+        .rpc();
+      expect.fail("Cancelling a fresh recovery should have failed.");
+    } catch (err) {
+      expect(err.toString()).to.include("RecoveryNotStale");
+    }
+
+    await program.methods
+      .approveRecovery()
+      .accountsStrict({
+        guardian: guardian.publicKey,
+        vaultState: guardedStatePda,
+        recovery: recoveryPda,
+      })
+      .signers([guardian])
+      .rpc();
+    recovery = await program.account.recoveryRequest.fetch(recoveryPda);
+    expect(recovery.executableAt.toNumber()).to.be.greaterThan(
+      Math.floor(Date.now() / 1000)
+    );
+    try {
+      await program.methods
+        .completeRecovery()
+        .accountsStrict(recoveryAccounts)
+        .rpc();
+      expect.fail("Completing before the timelock should have failed.");
+    } catch (err) {
+      expect(err.toString()).to.include("RecoveryTimelocked");
+    }
+
+    await program.methods
+      .vetoRecovery()
+      .accountsStrict({ signer: creator, ...recoveryAccounts })
+      .rpc();
+    expect(await provider.connection.getAccountInfo(recoveryPda)).to.be.null;
+    const state = await program.account.vaultState.fetch(guardedStatePda);
+    expect(state.owner.toBase58()).to.equal(creator.toBase58());
+  });
+
+  it("Stake part of the vault, then deactivate and withdraw it", async () => {
+    logSection("TEST: Staking");
+
+    const stakingId = new anchor.BN(6);
+    const [stakingStatePda] = findVaultState(creator, stakingId);
+    const [stakingVaultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("vault"),
+        creator.toBuffer(),
+        stakingStatePda.toBuffer(),
+      ],
+      program.programId
+    );
+    const [stakePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("stake"), stakingStatePda.toBuffer()],
+      program.programId
+    );
+    const vaultAccounts = {
+      signer: creator,
+      vaultState: stakingStatePda,
+      vault: stakingVaultPda,
+      systemProgram: SYSTEM_PROGRAM,
+    };
+    const stakeAccounts = {
+      signer: creator,
+      vaultState: stakingStatePda,
+      vault: stakingVaultPda,
+      stakeAccount: stakePda,
+      clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
+      stakeProgram: anchor.web3.StakeProgram.programId,
+    };
+
+    await program.methods
+      .initializeVault(stakingId, new anchor.BN(0))
+      .accounts(vaultAccounts)
+      .rpc();
+    await program.methods
+      .deposit(new anchor.BN(3 * LAMPORTS_PER_SOL))
+      .accounts(vaultAccounts)
+      .rpc();
+    const vaultBalance = await provider.connection.getBalance(stakingVaultPda);
+
+    // Lamports sent to the stake address beforehand don't block delegating
+    const donation =
+      await provider.connection.getMinimumBalanceForRentExemption(0);
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.transfer({
+          fromPubkey: creator,
+          toPubkey: stakePda,
+          lamports: donation,
+        })
+      )
+    );
+
+    const { current } = await provider.connection.getVoteAccounts();
+    const amount = 2 * LAMPORTS_PER_SOL;
+    await program.methods
+      .delegateStake(new anchor.BN(amount))
+      .accountsStrict({
+        ...stakeAccounts,
+        voteAccount: new anchor.web3.PublicKey(current[0].votePubkey),
+        stakeConfig: anchor.web3.STAKE_CONFIG_ID,
+        stakeHistory: anchor.web3.SYSVAR_STAKE_HISTORY_PUBKEY,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        systemProgram: SYSTEM_PROGRAM,
+      })
+      .rpc();
+
+    let state = await program.account.vaultState.fetch(stakingStatePda);
+    expect(state.staked.toNumber()).to.equal(amount);
+    expect(await provider.connection.getBalance(stakingVaultPda)).to.equal(
+      vaultBalance - amount
+    );
+
+    // Staked lamports aren't liquid, so they can't be withdrawn directly
+    try {
+      await program.methods
+        .withdraw(new anchor.BN(amount))
+        .accounts(vaultAccounts)
+        .rpc();
+      expect.fail("Withdrawing staked lamports should have failed.");
+    } catch (err) {
+      expect(err.toString()).to.include("InsufficientFunds");
+    }
+
+    // Stake deactivated in the epoch it was delegated is withdrawable at once
+    await program.methods
+      .deactivateStake()
+      .accountsStrict(stakeAccounts)
+      .rpc();
+    await program.methods
+      .withdrawStake()
+      .accountsStrict({
+        ...stakeAccounts,
+        stakeHistory: anchor.web3.SYSVAR_STAKE_HISTORY_PUBKEY,
+      })
+      .rpc();
+
+    // The donation comes back with the stake and is booked like rewards
+    state = await program.account.vaultState.fetch(stakingStatePda);
+    expect(state.staked.toNumber()).to.equal(0);
+    expect(await provider.connection.getBalance(stakingVaultPda)).to.equal(
+      vaultBalance + donation
+    );
+  });
+
+  it("Payout schedule pays accrued periods and settles on cancel", async () => {
+    logSection("TEST: Payout Schedule");
+
+    const payrollId = new anchor.BN(7);
+    const [payrollStatePda] = findVaultState(creator, payrollId);
+    const [payrollVaultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("vault"),
+        creator.toBuffer(),
+        payrollStatePda.toBuffer(),
+      ],
+      program.programId
+    );
+    const recipient = anchor.web3.Keypair.generate().publicKey;
+    const [payoutPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("payout"),
+        payrollStatePda.toBuffer(),
+        recipient.toBuffer(),
+      ],
+      program.programId
+    );
+    const vaultAccounts = {
+      signer: creator,
+      vaultState: payrollStatePda,
+      vault: payrollVaultPda,
+      systemProgram: SYSTEM_PROGRAM,
+    };
+
+    await program.methods
+      .initializeVault(payrollId, new anchor.BN(0))
+      .accounts(vaultAccounts)
+      .rpc();
+    await program.methods
+      .deposit(new anchor.BN(1 * LAMPORTS_PER_SOL))
+      .accounts(vaultAccounts)
+      .rpc();
+
+    const perPeriod = 0.01 * LAMPORTS_PER_SOL;
+    const endAt = Math.floor(Date.now() / 1000) + 60;
+    await program.methods
+      .createPayout(
+        recipient,
+        new anchor.BN(perPeriod),
+        new anchor.BN(1),
+        new anchor.BN(endAt)
+      )
+      .accountsStrict({
+        signer: creator,
+        vaultState: payrollStatePda,
+        payout: payoutPda,
+        systemProgram: SYSTEM_PROGRAM,
+      })
+      .rpc();
+
+    await new Promise((resolve) => setTimeout(resolve, 3000));
+    await program.methods
+      .crankPayout()
+      .accountsStrict({
+        vaultState: payrollStatePda,
+        vault: payrollVaultPda,
+        payout: payoutPda,
+        recipient,
+        systemProgram: SYSTEM_PROGRAM,
+      })
+      .rpc();
+
+    const payout = await program.account.payoutSchedule.fetch(payoutPda);
+    const cranked = await provider.connection.getBalance(recipient);
+    expect(payout.paidPeriods.toNumber()).to.be.greaterThan(0);
+    expect(cranked).to.equal(payout.paidPeriods.toNumber() * perPeriod);
+
+    await program.methods
+      .cancelPayout()
+      .accountsStrict({ ...vaultAccounts, payout: payoutPda, recipient })
+      .rpc();
+    expect(await provider.connection.getAccountInfo(payoutPda)).to.be.null;
+    expect(await provider.connection.getBalance(recipient)).to.be.at.least(
+      cranked
+    );
+
+    // Schedules end on a whole period, and a lock can't be used to cancel without paying
+    const latecomer = anchor.web3.Keypair.generate().publicKey;
+    const [latePayoutPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("payout"),
+        payrollStatePda.toBuffer(),
+        latecomer.toBuffer(),
+      ],
+      program.programId
+    );
+    const requestedEnd = Math.floor(Date.now() / 1000) + 65;
+    await program.methods
+      .createPayout(
+        latecomer,
+        new anchor.BN(perPeriod),
+        new anchor.BN(10),
+        new anchor.BN(requestedEnd)
+      )
+      .accountsStrict({
+        signer: creator,
+        vaultState: payrollStatePda,
+        payout: latePayoutPda,
+        systemProgram: SYSTEM_PROGRAM,
+      })
+      .rpc();
+    const late = await program.account.payoutSchedule.fetch(latePayoutPda);
+    expect((late.endAt.toNumber() - late.startAt.toNumber()) % 10).to.equal(0);
+    expect(late.endAt.toNumber()).to.be.at.most(requestedEnd);
+
+    await program.methods
+      .extendLock(new anchor.BN(Math.floor(Date.now() / 1000) + 3600))
+      .accounts({ signer: creator, vaultState: payrollStatePda })
+      .rpc();
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+    try {
+      await program.methods
+        .cancelPayout()
+        .accountsStrict({
+          ...vaultAccounts,
+          payout: latePayoutPda,
+          recipient: latecomer,
+        })
+        .rpc();
+      expect.fail("Cancelling without settling in full should have failed.");
+    } catch (err) {
+      expect(err.toString()).to.include("VaultLocked");
+    }
+    expect(await provider.connection.getAccountInfo(latePayoutPda)).to.not.be
+      .null;
+  });
+
+  it("Vesting grant pays out what has vested and can be revoked", async () => {
+    logSection("TEST: Vesting");
+
+    const grantsId = new anchor.BN(8);
+    const [grantsStatePda] = findVaultState(creator, grantsId);
+    const [grantsVaultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), creator.toBuffer(), grantsStatePda.toBuffer()],
+      program.programId
+    );
+    const [grantPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("vesting"),
+        grantsStatePda.toBuffer(),
+        user.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const vaultAccounts = {
+      signer: creator,
+      vaultState: grantsStatePda,
+      vault: grantsVaultPda,
+      systemProgram: SYSTEM_PROGRAM,
+    };
+
+    await program.methods
+      .initializeVault(grantsId, new anchor.BN(0))
+      .accounts(vaultAccounts)
+      .rpc();
+    await program.methods
+      .deposit(new anchor.BN(1 * LAMPORTS_PER_SOL))
+      .accounts(vaultAccounts)
+      .rpc();
+
+    // Started 100s ago, cliff already passed, fully vested in another 100s
+    const now = Math.floor(Date.now() / 1000);
+    const amount = 0.5 * LAMPORTS_PER_SOL;
+    await program.methods
+      .createVesting(user.publicKey, {
+        amount: new anchor.BN(amount),
+        startAt: new anchor.BN(now - 100),
+        cliffAt: new anchor.BN(now - 50),
+        duration: new anchor.BN(200),
+        revocable: true,
+      })
+      .accountsStrict({ ...vaultAccounts, grant: grantPda })
+      .rpc();
+
+    const userBalance = await provider.connection.getBalance(user.publicKey);
+    await program.methods
+      .claimVested()
+      .accountsStrict({
+        beneficiary: user.publicKey,
+        grant: grantPda,
+        funder: creator,
+      })
+      .signers([user])
+      .rpc();
+    const grant = await program.account.vestingGrant.fetch(grantPda);
+    const claimed = grant.claimed.toNumber();
+    expect(claimed).to.be.greaterThan(0).and.lessThan(amount);
+    expect(await provider.connection.getBalance(user.publicKey)).to.equal(
+      userBalance + claimed - 5000
+    );
+
+    const vaultBalance = await provider.connection.getBalance(grantsVaultPda);
+    await program.methods
+      .revokeVesting()
+      .accountsStrict({
+        signer: creator,
+        vaultState: grantsStatePda,
+        vault: grantsVaultPda,
+        grant: grantPda,
+        beneficiary: user.publicKey,
+        funder: creator,
+      })
+      .rpc();
+    expect(await provider.connection.getAccountInfo(grantPda)).to.be.null;
+    // The unvested part went back to the vault
+    expect(
+      await provider.connection.getBalance(grantsVaultPda)
+    ).to.be.greaterThan(vaultBalance);
+  });
+
+  it("Token grants sit next to SOL grants and sweep their ATA when they close", async () => {
+    logSection("TEST: Token Vesting");
+
+    const grantsId = new anchor.BN(11);
+    const [grantsStatePda] = findVaultState(creator, grantsId);
+    const [grantsVaultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), creator.toBuffer(), grantsStatePda.toBuffer()],
+      program.programId
+    );
+    const vaultAccounts = {
+      signer: creator,
+      vaultState: grantsStatePda,
+      vault: grantsVaultPda,
+      systemProgram: SYSTEM_PROGRAM,
+    };
+    await program.methods
+      .initializeVault(grantsId, new anchor.BN(0))
+      .accounts(vaultAccounts)
+      .rpc();
+    await program.methods
+      .deposit(new anchor.BN(0.5 * LAMPORTS_PER_SOL))
+      .accounts(vaultAccounts)
+      .rpc();
+
+    const mint = await createMint(
+      provider.connection,
+      provider.wallet.payer,
+      creator,
+      null,
+      6
+    );
+    const creatorAta = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer,
+      mint,
+      creator
+    );
+    await mintTo(
+      provider.connection,
+      provider.wallet.payer,
+      mint,
+      creatorAta.address,
+      creator,
+      1_000_000
+    );
+    const tokenAccounts = {
+      signer: creator,
+      vaultState: grantsStatePda,
+      mint,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+    await program.methods
+      .depositToken(new anchor.BN(1_000_000))
+      .accounts(tokenAccounts)
+      .rpc();
+
+    const [solGrantPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("vesting"),
+        grantsStatePda.toBuffer(),
+        user.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const [tokenGrantPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("vesting"),
+        grantsStatePda.toBuffer(),
+        user.publicKey.toBuffer(),
+        mint.toBuffer(),
+      ],
+      program.programId
+    );
+    const grantAta = getAssociatedTokenAddressSync(mint, tokenGrantPda, true);
+    const userAta = getAssociatedTokenAddressSync(mint, user.publicKey);
+
+    // Fully vested, and the beneficiary already has a SOL grant from this vault
+    const now = Math.floor(Date.now() / 1000);
+    const vested = {
+      startAt: new anchor.BN(now - 1000),
+      cliffAt: new anchor.BN(now - 1000),
+      duration: new anchor.BN(10),
+      revocable: false,
+    };
+    await program.methods
+      .createVesting(user.publicKey, {
+        ...vested,
+        amount: new anchor.BN(0.1 * LAMPORTS_PER_SOL),
+      })
+      .accountsStrict({ ...vaultAccounts, grant: solGrantPda })
+      .rpc();
+    // Creating the grant ATA up front doesn't block the grant
+    await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer,
+      mint,
+      tokenGrantPda,
+      true
+    );
+    await program.methods
+      .createTokenVesting(user.publicKey, {
+        ...vested,
+        amount: new anchor.BN(300_000),
+      })
+      .accounts({ ...tokenAccounts, grant: tokenGrantPda })
+      .rpc();
+    expect(await provider.connection.getAccountInfo(solGrantPda)).to.not.be
+      .null;
+
+    // Tokens sent straight to the grant ATA go out with the last claim
+    await mintTo(
+      provider.connection,
+      provider.wallet.payer,
+      mint,
+      grantAta,
+      creator,
+      5_000
+    );
+    await program.methods
+      .claimVestedToken()
+      .accounts({
+        beneficiary: user.publicKey,
+        grant: tokenGrantPda,
+        mint,
+        funder: creator,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([user])
+      .rpc();
+    expect(await provider.connection.getAccountInfo(tokenGrantPda)).to.be.null;
+    expect(await provider.connection.getAccountInfo(grantAta)).to.be.null;
+    expect(
+      Number((await getAccount(provider.connection, userAta)).amount)
+    ).to.equal(305_000);
+
+    // ...and go back to the vault on revoke
+    await program.methods
+      .createTokenVesting(user.publicKey, {
+        amount: new anchor.BN(200_000),
+        startAt: new anchor.BN(now - 100),
+        cliffAt: new anchor.BN(now - 100),
+        duration: new anchor.BN(10_000),
+        revocable: true,
+      })
+      .accounts({ ...tokenAccounts, grant: tokenGrantPda })
+      .rpc();
+    await mintTo(
+      provider.connection,
+      provider.wallet.payer,
+      mint,
+      grantAta,
+      creator,
+      7_000
+    );
+    await program.methods
+      .revokeTokenVesting()
+      .accounts({
+        ...tokenAccounts,
+        grant: tokenGrantPda,
+        beneficiary: user.publicKey,
+        funder: creator,
+      })
+      .rpc();
+    expect(await provider.connection.getAccountInfo(tokenGrantPda)).to.be.null;
+    expect(await provider.connection.getAccountInfo(grantAta)).to.be.null;
+    const vaultAta = getAssociatedTokenAddressSync(mint, grantsStatePda, true);
+    const paid =
+      Number((await getAccount(provider.connection, userAta)).amount) -
+      305_000;
+    expect(
+      Number((await getAccount(provider.connection, vaultAta)).amount)
+    ).to.equal(1_000_000 - 300_000 - paid + 7_000);
+  });
+
+  it("Multisig vault releases SOL only through approved proposals", async () => {
+    logSection("TEST: Multisig");
+
+    const treasuryId = new anchor.BN(9);
+    const [treasuryStatePda] = findVaultState(creator, treasuryId);
+    const [treasuryVaultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("vault"),
+        creator.toBuffer(),
+        treasuryStatePda.toBuffer(),
+      ],
+      program.programId
+    );
+    const treasury = {
+      signer: creator,
+      vaultState: treasuryStatePda,
+      vault: treasuryVaultPda,
+      systemProgram: SYSTEM_PROGRAM,
+    };
+    const recipient = anchor.web3.Keypair.generate().publicKey;
+    const findProposal = (id: number) =>
+      anchor.web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("proposal"),
+          treasuryStatePda.toBuffer(),
+          new anchor.BN(id).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      )[0];
+
+    await program.methods
+      .initializeVault(treasuryId, new anchor.BN(0))
+      .accounts(treasury)
+      .rpc();
+    await program.methods
+      .deposit(new anchor.BN(0.5 * LAMPORTS_PER_SOL))
+      .accounts(treasury)
+      .rpc();
+    // A delegate approved while the vault was single-creator...
+    const [allowancePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("delegate"),
+        treasuryStatePda.toBuffer(),
+        user.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    await program.methods
+      .approveDelegate(
+        user.publicKey,
+        new anchor.BN(0.1 * LAMPORTS_PER_SOL),
+        false
+      )
+      .accountsStrict({
+        signer: creator,
+        vaultState: treasuryStatePda,
+        delegateAllowance: allowancePda,
+        systemProgram: SYSTEM_PROGRAM,
+      })
+      .rpc();
+    await program.methods
+      .enableMultisig(
+        [creator, user.publicKey, anchor.web3.Keypair.generate().publicKey],
+        2
+      )
+      .accounts({ signer: creator, vaultState: treasuryStatePda })
+      .rpc();
+
+    try {
+      await program.methods
+        .withdraw(new anchor.BN(0.1 * LAMPORTS_PER_SOL))
+        .accounts(treasury)
+        .rpc();
+      expect.fail("Single-creator withdraw should have failed.");
+    } catch (err) {
+      expect(err.toString()).to.include("MultisigRequired");
+    }
+
+    // ...can no longer withdraw once it is a multisig
+    try {
+      await program.methods
+        .delegateWithdraw(new anchor.BN(0.05 * LAMPORTS_PER_SOL))
+        .accountsStrict({
+          delegate: user.publicKey,
+          vaultState: treasuryStatePda,
+          delegateAllowance: allowancePda,
+          vault: treasuryVaultPda,
+          systemProgram: SYSTEM_PROGRAM,
+        })
+        .signers([user])
+        .rpc();
+      expect.fail("Delegate withdraw from a multisig vault should have failed.");
+    } catch (err) {
+      expect(err.toString()).to.include("MultisigRequired");
+    }
+
+    // The owner alone can't lock the funds or cap them to a trickle either
+    try {
+      await program.methods
+        .extendLock(new anchor.BN(Math.floor(Date.now() / 1000) + 3600))
+        .accounts({ signer: creator, vaultState: treasuryStatePda })
+        .rpc();
+      expect.fail("Extending the lock on a multisig vault should have failed.");
+    } catch (err) {
+      expect(err.toString()).to.include("MultisigRequired");
+    }
+    try {
+      await program.methods
+        .setWithdrawCap(new anchor.BN(1))
+        .accountsStrict({ signer: creator, vaultState: treasuryStatePda })
+        .rpc();
+      expect.fail("Setting a cap on a multisig vault should have failed.");
+    } catch (err) {
+      expect(err.toString()).to.include("MultisigRequired");
+    }
+
+    const proposalPda = findProposal(0);
+    await program.methods
+      .proposeWithdraw(recipient, new anchor.BN(0.2 * LAMPORTS_PER_SOL))
+      .accountsStrict({
+        proposer: creator,
+        vaultState: treasuryStatePda,
+        proposal: proposalPda,
+        systemProgram: SYSTEM_PROGRAM,
+      })
+      .rpc();
+
+    const executeAccounts = {
+      signer: creator,
+      vaultState: treasuryStatePda,
+      proposal: proposalPda,
+      vault: treasuryVaultPda,
+      recipient,
+      proposer: creator,
+      systemProgram: SYSTEM_PROGRAM,
+    };
+    try {
+      await program.methods
+        .executeProposal()
+        .accountsStrict(executeAccounts)
+        .rpc();
+      expect.fail("Executing with one approval should have failed.");
+    } catch (err) {
+      expect(err.toString()).to.include("ThresholdNotMet");
+    }
+
+    await program.methods
+      .approveProposal()
+      .accountsStrict({
+        signer: user.publicKey,
+        vaultState: treasuryStatePda,
+        proposal: proposalPda,
+      })
+      .signers([user])
+      .rpc();
+    await program.methods
+      .executeProposal()
+      .accountsStrict(executeAccounts)
+      .rpc();
+
+    expect(await provider.connection.getBalance(recipient)).to.equal(
+      0.2 * LAMPORTS_PER_SOL
+    );
+    expect(await provider.connection.getAccountInfo(proposalPda)).to.be.null;
+
+    // The proposer can withdraw a proposal before it goes stale
+    const cancelledPda = findProposal(1);
+    await program.methods
+      .proposeWithdraw(recipient, new anchor.BN(0.1 * LAMPORTS_PER_SOL))
+      .accountsStrict({
+        proposer: creator,
+        vaultState: treasuryStatePda,
+        proposal: cancelledPda,
+        systemProgram: SYSTEM_PROGRAM,
+      })
+      .rpc();
+    await program.methods
+      .cancelProposal()
+      .accountsStrict({
+        signer: creator,
+        vaultState: treasuryStatePda,
+        proposal: cancelledPda,
+        proposer: creator,
+      })
+      .rpc();
+    expect(await provider.connection.getAccountInfo(cancelledPda)).to.be.null;
+  });
+
+  it("Tokens held before multisig leave through token proposals", async () => {
+    logSection("TEST: Multisig Token Proposal");
+
+    const safeId = new anchor.BN(10);
+    const [safeStatePda] = findVaultState(creator, safeId);
+    const [safeVaultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), creator.toBuffer(), safeStatePda.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .initializeVault(safeId, new anchor.BN(0))
+      .accounts({
+        signer: creator,
+        vaultState: safeStatePda,
+        vault: safeVaultPda,
+        systemProgram: SYSTEM_PROGRAM,
+      })
+      .rpc();
+
+    const mint = await createMint(
+      provider.connection,
+      provider.wallet.payer,
+      creator,
+      null,
+      6
+    );
+    const creatorAta = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer,
+      mint,
+      creator
+    );
+    await mintTo(
+      provider.connection,
+      provider.wallet.payer,
+      mint,
+      creatorAta.address,
+      creator,
+      1_000_000
+    );
+    await program.methods
+      .depositToken(new anchor.BN(400_000))
+      .accounts({
+        signer: creator,
+        vaultState: safeStatePda,
+        mint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    await program.methods
+      .enableMultisig([creator, user.publicKey], 2)
+      .accounts({ signer: creator, vaultState: safeStatePda })
+      .rpc();
+
+    const recipient = anchor.web3.Keypair.generate().publicKey;
+    const [proposalPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        safeStatePda.toBuffer(),
+        new anchor.BN(0).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    await program.methods
+      .proposeTokenWithdraw(recipient, new anchor.BN(150_000))
+      .accountsStrict({
+        proposer: creator,
+        vaultState: safeStatePda,
+        mint,
+        proposal: proposalPda,
+        systemProgram: SYSTEM_PROGRAM,
+      })
+      .rpc();
+    await program.methods
+      .approveProposal()
+      .accountsStrict({
+        signer: user.publicKey,
+        vaultState: safeStatePda,
+        proposal: proposalPda,
+      })
+      .signers([user])
+      .rpc();
+
+    // A token proposal can't be executed as a SOL withdraw
+    try {
+      await program.methods
+        .executeProposal()
+        .accountsStrict({
+          signer: creator,
+          vaultState: safeStatePda,
+          proposal: proposalPda,
+          vault: safeVaultPda,
+          recipient,
+          proposer: creator,
+          systemProgram: SYSTEM_PROGRAM,
+        })
+        .rpc();
+      expect.fail("Executing a token proposal as SOL should have failed.");
+    } catch (err) {
+      expect(err.toString()).to.include("ProposalAssetMismatch");
+    }
+
+    await program.methods
+      .executeTokenProposal()
+      .accounts({
+        signer: creator,
+        vaultState: safeStatePda,
+        proposal: proposalPda,
+        mint,
+        recipient,
+        proposer: creator,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    const vaultAta = getAssociatedTokenAddressSync(mint, safeStatePda, true);
+    const recipientAta = getAssociatedTokenAddressSync(mint, recipient);
+    expect(
+      Number((await getAccount(provider.connection, vaultAta)).amount)
+    ).to.equal(250_000);
+    expect(
+      Number((await getAccount(provider.connection, recipientAta)).amount)
+    ).to.equal(150_000);
+    expect(await provider.connection.getAccountInfo(proposalPda)).to.be.null;
+  });
+
+  it("Deposit, withdraw and close an SPL token vault", async () => {
+    logSection("TEST: Token Vault");
+
+    const mint = await createMint(
+      provider.connection,
+      provider.wallet.payer,
+      creator,
+      null,
+      6
+    );
+    const creatorAta = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer,
+      mint,
+      creator
+    );
+    await mintTo(
+      provider.connection,
+      provider.wallet.payer,
+      mint,
+      creatorAta.address,
+      creator,
+      1_000_000
+    );
+    // Token vaults are ATAs owned by the vault_state PDA
+    const vaultAta = getAssociatedTokenAddressSync(mint, vaultStatePda, true);
+    const accounts = {
+      signer: creator,
+      vaultState: vaultStatePda,
+      mint,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+
+    await program.methods
+      .depositToken(new anchor.BN(600_000))
+      .accounts(accounts)
+      .rpc();
+    expect(
+      Number((await getAccount(provider.connection, vaultAta)).amount)
+    ).to.equal(600_000);
+
+    await program.methods
+      .withdrawToken(new anchor.BN(100_000))
+      .accounts(accounts)
+      .rpc();
+    expect(
+      Number((await getAccount(provider.connection, vaultAta)).amount)
+    ).to.equal(500_000);
+
+    try {
+      await program.methods
+        .withdrawToken(new anchor.BN(600_000))
+        .accounts(accounts)
+        .rpc();
+      expect.fail("Withdrawing more than the token vault holds should fail.");
+    } catch (err) {
+      expect(err.toString()).to.include("InsufficientFunds");
+    }
+
+    const tx = await program.methods
+      .closeTokenVault()
+      .accounts(accounts)
+      .rpc();
+    logTransactionResult("Token vault closed", tx);
+
+    expect(await provider.connection.getAccountInfo(vaultAta)).to.be.null;
+    expect(
+      Number((await getAccount(provider.connection, creatorAta.address)).amount)
+    ).to.equal(1_000_000);
+  });
+
+  it("Token vault refuses a Token-2022 mint with a transfer fee", async () => {
+    logSection("TEST: Transfer Fee Mint");
+
+    const mint = anchor.web3.Keypair.generate();
+    const space = getMintLen([ExtensionType.TransferFeeConfig]);
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.createAccount({
+          fromPubkey: creator,
+          newAccountPubkey: mint.publicKey,
+          space,
+          lamports:
+            await provider.connection.getMinimumBalanceForRentExemption(space),
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(
+          mint.publicKey,
+          creator,
+          creator,
+          100,
+          BigInt(1_000),
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(
+          mint.publicKey,
+          6,
+          creator,
+          null,
+          TOKEN_2022_PROGRAM_ID
+        )
+      ),
+      [mint]
+    );
+    const creatorAta = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet.payer,
+      mint.publicKey,
+      creator,
+      false,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await mintTo(
+      provider.connection,
+      provider.wallet.payer,
+      mint.publicKey,
+      creatorAta.address,
+      creator,
+      1_000_000,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    try {
+      await program.methods
+        .depositToken(new anchor.BN(100_000))
+        .accounts({
+          signer: creator,
+          vaultState: vaultStatePda,
+          mint: mint.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+      expect.fail("Depositing a transfer-fee mint should fail.");
+    } catch (err) {
+      expect(err.toString()).to.include("TransferFeeMint");
+    }
+  });
+
+  it("Time-locked vault refuses withdraw and close until unlock_at", async () => {
+    logSection("TEST: Time Lock");
+
+    const [userStatePda] = findVaultState(user.publicKey, vaultId);
+    const [userVaultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("vault"),
+        user.publicKey.toBuffer(),
+        userStatePda.toBuffer(),
+      ],
+      program.programId
+    );
+    const accounts = {
+      signer: user.publicKey,
+      vaultState: userStatePda,
+      vault: userVaultPda,
+      systemProgram: SYSTEM_PROGRAM,
+    };
+    const unlockAt = Math.floor(Date.now() / 1000) + 3600;
+
+    await program.methods
+      .initializeVault(vaultId, new anchor.BN(unlockAt))
+      .accounts(accounts)
+      .signers([user])
+      .rpc();
+    await program.methods
+      .deposit(new anchor.BN(0.1 * LAMPORTS_PER_SOL))
+      .accounts(accounts)
+      .signers([user])
+      .rpc();
+
+    try {
+      await program.methods
+        .withdraw(new anchor.BN(0.1 * LAMPORTS_PER_SOL))
+        .accounts(accounts)
+        .signers([user])
+        .rpc();
+      expect.fail("Withdraw before unlock_at should have failed.");
+    } catch (err) {
+      expect(err.toString()).to.include("VaultLocked");
+    }
+    try {
+      await program.methods
+        .close()
+        .accountsStrict(accounts)
+        .signers([user])
+        .rpc();
+      expect.fail("Close before unlock_at should have failed.");
+    } catch (err) {
+      expect(err.toString()).to.include("VaultLocked");
+    }
+
+    // The lock can be pushed out but never brought forward
+    try {
+      await program.methods
+        .extendLock(new anchor.BN(unlockAt - 60))
+        .accounts({ signer: user.publicKey, vaultState: userStatePda })
+        .signers([user])
+        .rpc();
+      expect.fail("Shortening the lock should have failed.");
+    } catch (err) {
+      expect(err.toString()).to.include("InvalidUnlockTime");
+    }
+    await program.methods
+      .extendLock(new anchor.BN(unlockAt + 3600))
+      .accounts({ signer: user.publicKey, vaultState: userStatePda })
+      .signers([user])
+      .rpc();
+
+    const state = await program.account.vaultState.fetch(userStatePda);
+    expect(state.unlockAt.toNumber()).to.equal(unlockAt + 3600);
+  });
+
   it("Close the vault", async () => {
     logSection("TEST: Close Vault");
 
@@ -1 +1,28 @@
+use anchor_spl::token_2022::spl_token_2022::extension::ExtensionType;
+
 pub const ESCROW_SEED: &[u8] = b"escrow";
+// Bumped whenever the `Escrow` layout changes; see `migrate_escrow`
+pub const ESCROW_VERSION: u8 = 1;
+pub const ESCROW_RESERVED_LEN: usize = 64;
+pub const CONFIG_SEED: &[u8] = b"config";
+// Hard cap on the protocol fee: 10%
+pub const MAX_FEE_BPS: u16 = 1_000;
+pub const BPS_DENOMINATOR: u64 = 10_000;
+pub const BASKET_SEED: &[u8] = b"basket";
+// Upper bound on legs per side of a basket offer
+pub const MAX_BASKET_LEGS: usize = 5;
+// Token-2022 mint extensions an escrow can hold or ask for. Anything else
+// (non-transferable, permanent delegate, default-frozen accounts, ...) could
+// strand or drain the vault, so `make` rejects it.
+pub const SUPPORTED_MINT_EXTENSIONS: &[ExtensionType] = &[
+    ExtensionType::TransferFeeConfig,
+    ExtensionType::TransferHook,
+    ExtensionType::MintCloseAuthority,
+    ExtensionType::InterestBearingConfig,
+    ExtensionType::MetadataPointer,
+    ExtensionType::TokenMetadata,
+    ExtensionType::GroupPointer,
+    ExtensionType::TokenGroup,
+    ExtensionType::GroupMemberPointer,
+    ExtensionType::TokenGroupMember,
+];
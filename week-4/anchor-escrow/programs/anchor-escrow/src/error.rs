@@ -6,4 +6,43 @@ pub enum EscrowError {
 
     #[msg("Invalid escrow status for this operation")]
     InvalidStatus,
+
+    #[msg("Fill amount exceeds what is left in the escrow")]
+    FillTooLarge,
+
+    #[msg("Fill amount is too small to receive any tokens")]
+    FillTooSmall,
+
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+
+    #[msg("Expiry must be in the future")]
+    InvalidExpiry,
+
+    #[msg("Escrow offer has expired")]
+    EscrowExpired,
+
+    #[msg("Escrow offer has not expired yet")]
+    EscrowNotExpired,
+
+    #[msg("Signer is not the designated taker for this escrow")]
+    UnauthorizedTaker,
+
+    #[msg("Fee exceeds the maximum allowed basis points")]
+    FeeTooHigh,
+
+    #[msg("Basket must have between 1 and MAX_BASKET_LEGS legs per side")]
+    InvalidLegCount,
+
+    #[msg("Basket legs must not repeat a mint")]
+    DuplicateLegMint,
+
+    #[msg("Remaining accounts do not match the basket legs")]
+    InvalidLegAccount,
+
+    #[msg("Escrow account is already on the current layout")]
+    AlreadyMigrated,
+
+    #[msg("Mint uses a Token-2022 extension the escrow does not support")]
+    UnsupportedMintExtension,
 }
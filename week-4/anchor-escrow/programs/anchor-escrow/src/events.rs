@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Leg;
+
+#[event]
+pub struct EscrowMade {
+    pub escrow: Pubkey,
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub deposit_amount: u64,
+    pub receive_amount: u64,
+    pub seed: u64,
+    pub slot: u64,
+}
+
+// Emitted for full and partial takes; `remaining_amount` is 0 once the escrow closes
+#[event]
+pub struct EscrowTaken {
+    pub escrow: Pubkey,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub remaining_amount: u64,
+    pub seed: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct EscrowRefunded {
+    pub escrow: Pubkey,
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub amount: u64,
+    pub seed: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct EscrowExpired {
+    pub escrow: Pubkey,
+    pub maker: Pubkey,
+    pub caller: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub amount: u64,
+    pub seed: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct EscrowUpdated {
+    pub escrow: Pubkey,
+    pub maker: Pubkey,
+    pub seed: u64,
+    pub old_receive_amount: u64,
+    pub receive_amount: u64,
+    pub old_deposit_amount: u64,
+    pub deposit_amount: u64,
+    pub old_mint_b: Pubkey,
+    pub mint_b: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct BasketMade {
+    pub basket: Pubkey,
+    pub maker: Pubkey,
+    pub offered: Vec<Leg>,
+    pub requested: Vec<Leg>,
+    pub seed: u64,
+    pub slot: u64,
+}
+
+// `offered` carries what each vault actually released, donations included
+#[event]
+pub struct BasketTaken {
+    pub basket: Pubkey,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub offered: Vec<Leg>,
+    pub requested: Vec<Leg>,
+    pub seed: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct BasketRefunded {
+    pub basket: Pubkey,
+    pub maker: Pubkey,
+    pub offered: Vec<Leg>,
+    pub seed: u64,
+    pub slot: u64,
+}
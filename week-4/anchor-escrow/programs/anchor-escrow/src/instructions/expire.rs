@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    constants::ESCROW_SEED,
+    error::EscrowError,
+    events::EscrowExpired,
+    instructions::refund::{close_vault, refund_vault},
+    state::{Escrow, EscrowStatus},
+};
+
+// Permissionless: anyone can return an expired offer to its maker
+#[derive(Accounts)]
+pub struct ExpireAccounts<'info> {
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds=[
+            ESCROW_SEED,
+            escrow.seed.to_le_bytes().as_ref(),
+            maker.key().as_ref()],
+        bump,
+        has_one=mint_a,
+        has_one=maker,
+        close=maker
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    // Writable so withheld transfer fees can be harvested before the vault closes
+    #[account(
+        mut,
+        mint::token_program=token_program
+    )]
+    pub mint_a: InterfaceAccount<'info, Mint>,
+
+    // Vault: Associated Token Account owned by Escrow PDA
+    #[account(
+        mut,
+        associated_token::mint=mint_a,
+        associated_token::authority=escrow,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    // Maker's token account for mint A
+    #[account(
+        init_if_needed,
+        payer=caller,
+        associated_token::mint=mint_a,
+        associated_token::authority=maker,
+        associated_token::token_program = token_program
+    )]
+    pub maker_ata_a: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ExpireAccounts<'info> {
+    pub fn expire_handler(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        require!(
+            self.escrow.is_expired(Clock::get()?.unix_timestamp),
+            EscrowError::EscrowNotExpired
+        );
+        let amount = self.vault.amount;
+        refund_vault(
+            &self.escrow,
+            &self.vault,
+            &self.mint_a,
+            &self.maker_ata_a,
+            &self.token_program,
+            remaining_accounts,
+        )?;
+        close_vault(
+            &self.escrow,
+            &self.vault,
+            &self.mint_a,
+            &self.maker.to_account_info(),
+            &self.token_program,
+        )?;
+        self.escrow.status = EscrowStatus::Expired;
+
+        emit!(EscrowExpired {
+            escrow: self.escrow.key(),
+            maker: self.maker.key(),
+            caller: self.caller.key(),
+            mint_a: self.mint_a.key(),
+            mint_b: self.escrow.mint_b,
+            amount,
+            seed: self.escrow.seed,
+            slot: Clock::get()?.slot,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{CONFIG_SEED, MAX_FEE_BPS},
+    error::EscrowError,
+    program::AnchorEscrow,
+    state::Config,
+};
+
+#[derive(Accounts)]
+pub struct InitializeConfigAccounts<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        payer=admin,
+        space= Config::DISCRIMINATOR.len() + Config::INIT_SPACE,
+        seeds=[CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    // Only the program's upgrade authority can create the config
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, AnchorEscrow>,
+
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()))]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeConfigAccounts<'info> {
+    pub fn initialize_config_handler(
+        &mut self,
+        fee_bps: u16,
+        treasury: Pubkey,
+        bump: &InitializeConfigAccountsBumps,
+    ) -> Result<()> {
+        require!(fee_bps <= MAX_FEE_BPS, EscrowError::FeeTooHigh);
+
+        self.config.set_inner(Config {
+            admin: self.admin.key(),
+            treasury,
+            fee_bps,
+            bump: bump.config,
+        });
+        Ok(())
+    }
+}
@@ -1,13 +1,15 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
 use crate::{
-    constants::ESCROW_SEED,
+    constants::{ESCROW_RESERVED_LEN, ESCROW_SEED, ESCROW_VERSION},
     error::EscrowError,
+    events::EscrowMade,
     state::{Escrow, EscrowStatus},
+    token_extensions::{check_mint_extensions, gross_up, transfer_checked},
 };
 
 #[derive(Accounts)]
@@ -19,7 +21,7 @@ pub struct MakerAccounts<'info> {
     #[account(
         init,
         payer=maker,
-        space= Escrow::DISCRIMINATOR.len() + Escrow::LEN,
+        space= Escrow::DISCRIMINATOR.len() + Escrow::INIT_SPACE,
         seeds=[
             ESCROW_SEED,
             seed.to_le_bytes().as_ref(),
@@ -58,49 +60,92 @@ pub struct MakerAccounts<'info> {
     pub system_program: Program<'info, System>,
 }
 impl<'info> MakerAccounts<'info> {
+    #[allow(clippy::too_many_arguments)]
     pub fn make_handler(
         &mut self,
         seed: u64,
         receive_amount: u64,
         deposit_amount: u64,
+        expires_at: i64,
+        allowed_taker: Option<Pubkey>,
         bump: &MakerAccountsBumps,
+        remaining_accounts: &[AccountInfo<'info>],
     ) -> Result<()> {
         //Initialize escrow
-        self.initialize_escrow(seed, receive_amount, bump)?;
+        self.initialize_escrow(
+            seed,
+            receive_amount,
+            deposit_amount,
+            expires_at,
+            allowed_taker,
+            bump,
+        )?;
 
         //Deposit NFT
-        self.deposit_nft(deposit_amount)?;
+        self.deposit_nft(deposit_amount, remaining_accounts)?;
 
+        emit!(EscrowMade {
+            escrow: self.escrow.key(),
+            maker: self.maker.key(),
+            mint_a: self.mint_a.key(),
+            mint_b: self.mint_b.key(),
+            deposit_amount,
+            receive_amount,
+            seed,
+            slot: Clock::get()?.slot,
+        });
         Ok(())
     }
     fn initialize_escrow(
         &mut self,
         seed: u64,
         receive_amount: u64,
+        deposit_amount: u64,
+        expires_at: i64,
+        allowed_taker: Option<Pubkey>,
         bump: &MakerAccountsBumps,
     ) -> Result<()> {
         require!(receive_amount > 0, EscrowError::InvalidAmount);
+        check_mint_extensions(&self.mint_a)?;
+        check_mint_extensions(&self.mint_b)?;
+        require!(
+            expires_at == 0 || expires_at > Clock::get()?.unix_timestamp,
+            EscrowError::InvalidExpiry
+        );
 
         self.escrow.set_inner(Escrow {
+            version: ESCROW_VERSION,
             maker: self.maker.key(),
             mint_a: self.mint_a.key(),
             mint_b: self.mint_b.key(),
             receive_amount,
+            deposit_amount,
+            remaining_amount: deposit_amount,
+            filled_amount: 0,
+            expires_at,
+            allowed_taker,
             status: EscrowStatus::Initialized,
             seed,
             bump: bump.escrow,
+            reserved: [0; ESCROW_RESERVED_LEN],
         });
         Ok(())
     }
 
-    fn deposit_nft(&mut self, deposit_amount: u64) -> Result<()> {
+    fn deposit_nft(
+        &mut self,
+        deposit_amount: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
         require!(
             self.escrow.status == EscrowStatus::Initialized,
             EscrowError::InvalidStatus
         );
         require!(deposit_amount > 0, EscrowError::InvalidAmount);
 
-        // Transfer the A from the maker to the vault
+        // Transfer the A from the maker to the vault, covering any transfer fee
+        // so the vault holds exactly `deposit_amount`
+        let amount = gross_up(&self.mint_a, deposit_amount)?;
         let cpi_ctx = CpiContext::new(
             self.token_program.to_account_info(),
             TransferChecked {
@@ -109,9 +154,10 @@ impl<'info> MakerAccounts<'info> {
                 to: self.vault.to_account_info(),
                 authority: self.maker.to_account_info(),
             },
-        );
+        )
+        .with_remaining_accounts(remaining_accounts.to_vec());
 
-        transfer_checked(cpi_ctx, deposit_amount, self.mint_a.decimals)?;
+        transfer_checked(cpi_ctx, amount, self.mint_a.decimals)?;
 
         // Update the escrow status
         self.escrow.status = EscrowStatus::Deposited;
@@ -0,0 +1,176 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{
+        create_idempotent, get_associated_token_address_with_program_id, AssociatedToken, Create,
+    },
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::{BASKET_SEED, MAX_BASKET_LEGS},
+    error::EscrowError,
+    events::BasketMade,
+    state::{BasketEscrow, EscrowStatus, Leg},
+    token_extensions::{check_mint_extensions, gross_up, transfer_checked},
+};
+
+// remaining_accounts, per offered leg:   [mint, maker_ata, vault]
+//                     per requested leg: [mint]
+//                     then the hook accounts of any transfer-hook mints
+#[derive(Accounts)]
+#[instruction(seed:u64)]
+pub struct MakeBasketAccounts<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        init,
+        payer=maker,
+        space= BasketEscrow::DISCRIMINATOR.len() + BasketEscrow::INIT_SPACE,
+        seeds=[
+            BASKET_SEED,
+            seed.to_le_bytes().as_ref(),
+            maker.key().as_ref()],
+        bump
+    )]
+    pub basket: Account<'info, BasketEscrow>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MakeBasketAccounts<'info> {
+    pub fn make_basket_handler(
+        &mut self,
+        seed: u64,
+        offered: Vec<Leg>,
+        requested: Vec<Leg>,
+        bump: &MakeBasketAccountsBumps,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        validate_legs(&offered)?;
+        validate_legs(&requested)?;
+        let (offered_accounts, rest) = remaining_accounts
+            .split_at_checked(offered.len() * 3)
+            .ok_or(EscrowError::InvalidLegAccount)?;
+        let (requested_mints, _) = rest
+            .split_at_checked(requested.len())
+            .ok_or(EscrowError::InvalidLegAccount)?;
+
+        // Refuse mints the taker could never pay with before anything is deposited
+        let token_program = self.token_program.key();
+        for (leg, mint) in requested.iter().zip(requested_mints) {
+            load_leg_mint(leg, mint, &token_program)?;
+            check_mint_extensions(mint)?;
+        }
+
+        for (leg, accounts) in offered.iter().zip(offered_accounts.chunks(3)) {
+            let [mint, maker_ata, vault] = accounts else {
+                return err!(EscrowError::InvalidLegAccount);
+            };
+            self.deposit_leg(leg, mint, maker_ata, vault, remaining_accounts)?;
+        }
+
+        emit!(BasketMade {
+            basket: self.basket.key(),
+            maker: self.maker.key(),
+            offered: offered.clone(),
+            requested: requested.clone(),
+            seed,
+            slot: Clock::get()?.slot,
+        });
+
+        self.basket.set_inner(BasketEscrow {
+            maker: self.maker.key(),
+            offered,
+            requested,
+            seed,
+            bump: bump.basket,
+            status: EscrowStatus::Deposited,
+        });
+        Ok(())
+    }
+
+    // Creates the basket's vault for this leg and moves the maker's tokens in.
+    // Any transfer fee is paid on top, so the vault holds exactly `leg.amount`.
+    fn deposit_leg(
+        &self,
+        leg: &Leg,
+        mint: &AccountInfo<'info>,
+        maker_ata: &AccountInfo<'info>,
+        vault: &AccountInfo<'info>,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let token_program = self.token_program.key();
+        let decimals = load_leg_mint(leg, mint, &token_program)?.decimals;
+        check_mint_extensions(mint)?;
+        check_leg_ata(maker_ata, &self.maker.key(), leg, &token_program)?;
+        check_leg_ata(vault, &self.basket.key(), leg, &token_program)?;
+
+        create_idempotent(CpiContext::new(
+            self.associated_token_program.to_account_info(),
+            Create {
+                payer: self.maker.to_account_info(),
+                associated_token: vault.clone(),
+                authority: self.basket.to_account_info(),
+                mint: mint.clone(),
+                system_program: self.system_program.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+            },
+        ))?;
+
+        let cpi_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            TransferChecked {
+                from: maker_ata.clone(),
+                mint: mint.clone(),
+                to: vault.clone(),
+                authority: self.maker.to_account_info(),
+            },
+        )
+        .with_remaining_accounts(remaining_accounts.to_vec());
+        let amount = gross_up(mint, leg.amount)?;
+        transfer_checked(cpi_ctx, amount, decimals)
+    }
+}
+
+fn validate_legs(legs: &[Leg]) -> Result<()> {
+    require!(
+        !legs.is_empty() && legs.len() <= MAX_BASKET_LEGS,
+        EscrowError::InvalidLegCount
+    );
+    for (i, leg) in legs.iter().enumerate() {
+        require!(leg.amount > 0, EscrowError::InvalidAmount);
+        require!(
+            legs[..i].iter().all(|other| other.mint != leg.mint),
+            EscrowError::DuplicateLegMint
+        );
+    }
+    Ok(())
+}
+
+// Shared by the basket instructions: checks a leg's mint account and reads it
+pub(crate) fn load_leg_mint(leg: &Leg, mint: &AccountInfo, token_program: &Pubkey) -> Result<Mint> {
+    require_keys_eq!(mint.key(), leg.mint, EscrowError::InvalidLegAccount);
+    require_keys_eq!(*mint.owner, *token_program, EscrowError::InvalidLegAccount);
+    Mint::try_deserialize(&mut &mint.try_borrow_data()?[..])
+}
+
+pub(crate) fn check_leg_ata(
+    ata: &AccountInfo,
+    owner: &Pubkey,
+    leg: &Leg,
+    token_program: &Pubkey,
+) -> Result<()> {
+    require_keys_eq!(
+        ata.key(),
+        get_associated_token_address_with_program_id(owner, &leg.mint, token_program),
+        EscrowError::InvalidLegAccount
+    );
+    Ok(())
+}
+
+pub(crate) fn load_leg_vault(vault: &AccountInfo) -> Result<TokenAccount> {
+    TokenAccount::try_deserialize(&mut &vault.try_borrow_data()?[..])
+}
@@ -0,0 +1,105 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    constants::{ESCROW_RESERVED_LEN, ESCROW_VERSION},
+    error::EscrowError,
+    state::{Escrow, EscrowV0},
+};
+
+// Permissionless: rewrites a pre-versioning escrow into the current layout
+#[derive(Accounts)]
+pub struct MigrateEscrowAccounts<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: legacy layout that `Account<Escrow>` can't load, validated in the handler
+    #[account(mut, owner=crate::ID)]
+    pub escrow: UncheckedAccount<'info>,
+
+    #[account(mint::token_program=token_program)]
+    pub mint_a: InterfaceAccount<'info, Mint>,
+
+    // Vault: Associated Token Account owned by Escrow PDA
+    #[account(
+        associated_token::mint=mint_a,
+        associated_token::authority=escrow,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateEscrowAccounts<'info> {
+    pub fn migrate_escrow_handler(&mut self) -> Result<()> {
+        let legacy = self.load_legacy()?;
+        require_keys_eq!(
+            legacy.mint_a,
+            self.mint_a.key(),
+            ErrorCode::ConstraintHasOne
+        );
+
+        self.realloc()?;
+
+        let escrow = Escrow {
+            version: ESCROW_VERSION,
+            maker: legacy.maker,
+            mint_a: legacy.mint_a,
+            mint_b: legacy.mint_b,
+            receive_amount: legacy.receive_amount,
+            // v0 had no partial fills, so whatever is in the vault is the whole deposit
+            deposit_amount: self.vault.amount,
+            remaining_amount: self.vault.amount,
+            filled_amount: 0,
+            expires_at: 0,
+            allowed_taker: None,
+            seed: legacy.seed,
+            bump: legacy.bump,
+            status: legacy.status,
+            reserved: [0; ESCROW_RESERVED_LEN],
+        };
+        let mut data = self.escrow.try_borrow_mut_data()?;
+        escrow.try_serialize(&mut &mut data[..])?;
+        Ok(())
+    }
+
+    fn load_legacy(&self) -> Result<EscrowV0> {
+        let data = self.escrow.try_borrow_data()?;
+        require!(
+            data.len() == Escrow::DISCRIMINATOR.len() + EscrowV0::LEN,
+            EscrowError::AlreadyMigrated
+        );
+        require!(
+            data.starts_with(Escrow::DISCRIMINATOR),
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+        Ok(EscrowV0::deserialize(
+            &mut &data[Escrow::DISCRIMINATOR.len()..],
+        )?)
+    }
+
+    // Grows the account to the current layout, with the payer topping up rent
+    fn realloc(&self) -> Result<()> {
+        let new_len = Escrow::DISCRIMINATOR.len() + Escrow::INIT_SPACE;
+        let rent_due = Rent::get()?
+            .minimum_balance(new_len)
+            .saturating_sub(self.escrow.lamports());
+        if rent_due > 0 {
+            let cpi_ctx = CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.payer.to_account_info(),
+                    to: self.escrow.to_account_info(),
+                },
+            );
+            transfer(cpi_ctx, rent_due)?;
+        }
+        self.escrow.resize(new_len)?;
+        Ok(())
+    }
+}
@@ -4,3 +4,19 @@ pub mod take;
 pub use take::*;
 pub mod refund;
 pub use refund::*;
+pub mod expire;
+pub use expire::*;
+pub mod initialize_config;
+pub use initialize_config::*;
+pub mod update_config;
+pub use update_config::*;
+pub mod make_basket;
+pub use make_basket::*;
+pub mod take_basket;
+pub use take_basket::*;
+pub mod refund_basket;
+pub use refund_basket::*;
+pub mod update_offer;
+pub use update_offer::*;
+pub mod migrate_escrow;
+pub use migrate_escrow::*;
@@ -2,15 +2,16 @@ use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
     token_interface::{
-        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
-        TransferChecked,
+        close_account, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
     },
 };
 
 use crate::{
     constants::ESCROW_SEED,
     error::EscrowError,
+    events::EscrowRefunded,
     state::{Escrow, EscrowStatus},
+    token_extensions::{harvest_vault_fees, transfer_checked},
 };
 
 #[derive(Accounts)]
@@ -31,7 +32,9 @@ pub struct RefundAccounts<'info> {
     )]
     pub escrow: Account<'info, Escrow>,
 
+    // Writable so withheld transfer fees can be harvested before the vault closes
     #[account(
+        mut,
         mint::token_program=token_program
     )]
     pub mint_a: InterfaceAccount<'info, Mint>,
@@ -60,57 +63,105 @@ pub struct RefundAccounts<'info> {
 }
 
 impl<'info> RefundAccounts<'info> {
-    pub fn refund_handler(&mut self) -> Result<()> {
-        self.refund()?;
+    pub fn refund_handler(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let amount = self.vault.amount;
+        self.refund(remaining_accounts)?;
         self.close_vault()?;
         self.escrow.status = EscrowStatus::Refunded;
+
+        emit!(EscrowRefunded {
+            escrow: self.escrow.key(),
+            maker: self.maker.key(),
+            mint_a: self.mint_a.key(),
+            mint_b: self.escrow.mint_b,
+            amount,
+            seed: self.escrow.seed,
+            slot: Clock::get()?.slot,
+        });
         Ok(())
     }
 
     // transfer token A from vault to maker Token A ATA
-    fn refund(&self) -> Result<()> {
-        require!(
-            self.escrow.status == EscrowStatus::Deposited,
-            EscrowError::InvalidStatus
-        );
-        let signer_seeds: &[&[&[u8]]] = &[&[
-            ESCROW_SEED,
-            &self.escrow.seed.to_le_bytes(),
-            self.maker.key.as_ref(),
-            &[self.escrow.bump],
-        ]];
-        let cpi_ctx = CpiContext::new_with_signer(
-            self.token_program.to_account_info(),
-            TransferChecked {
-                from: self.vault.to_account_info(),
-                mint: self.mint_a.to_account_info(),
-                to: self.maker_ata_a.to_account_info(),
-                authority: self.escrow.to_account_info(),
-            },
-            signer_seeds,
-        );
-        transfer_checked(cpi_ctx, self.vault.amount, self.mint_a.decimals)?;
-        Ok(())
+    fn refund(&self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        refund_vault(
+            &self.escrow,
+            &self.vault,
+            &self.mint_a,
+            &self.maker_ata_a,
+            &self.token_program,
+            remaining_accounts,
+        )
     }
 
     fn close_vault(&self) -> Result<()> {
-        let signer_seeds: &[&[&[u8]]] = &[&[
-            ESCROW_SEED,
-            &self.escrow.seed.to_le_bytes(),
-            self.maker.key.as_ref(),
-            &[self.escrow.bump],
-        ]];
-
-        let cpi_ctx = CpiContext::new_with_signer(
-            self.token_program.to_account_info(),
-            CloseAccount {
-                account: self.vault.to_account_info(),
-                destination: self.maker_ata_a.to_account_info(),
-                authority: self.escrow.to_account_info(),
-            },
-            signer_seeds,
-        );
-        close_account(cpi_ctx)?;
-        Ok(())
+        close_vault(
+            &self.escrow,
+            &self.vault,
+            &self.mint_a,
+            &self.maker_ata_a.to_account_info(),
+            &self.token_program,
+        )
     }
 }
+
+// Shared with `expire`: moves everything left in the vault back to the maker
+pub(crate) fn refund_vault<'info>(
+    escrow: &Account<'info, Escrow>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    mint_a: &InterfaceAccount<'info, Mint>,
+    maker_ata_a: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    require!(
+        escrow.status == EscrowStatus::Deposited || escrow.status == EscrowStatus::PartiallyFilled,
+        EscrowError::InvalidStatus
+    );
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        ESCROW_SEED,
+        &escrow.seed.to_le_bytes(),
+        escrow.maker.as_ref(),
+        &[escrow.bump],
+    ]];
+    let cpi_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        TransferChecked {
+            from: vault.to_account_info(),
+            mint: mint_a.to_account_info(),
+            to: maker_ata_a.to_account_info(),
+            authority: escrow.to_account_info(),
+        },
+        signer_seeds,
+    )
+    .with_remaining_accounts(remaining_accounts.to_vec());
+    transfer_checked(cpi_ctx, vault.amount, mint_a.decimals)?;
+    Ok(())
+}
+
+pub(crate) fn close_vault<'info>(
+    escrow: &Account<'info, Escrow>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    mint_a: &InterfaceAccount<'info, Mint>,
+    destination: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    harvest_vault_fees(token_program, mint_a, vault)?;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        ESCROW_SEED,
+        &escrow.seed.to_le_bytes(),
+        escrow.maker.as_ref(),
+        &[escrow.bump],
+    ]];
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: vault.to_account_info(),
+            destination: destination.to_account_info(),
+            authority: escrow.to_account_info(),
+        },
+        signer_seeds,
+    );
+    close_account(cpi_ctx)?;
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{close_account, CloseAccount, TokenInterface, TransferChecked};
+
+use crate::{
+    constants::BASKET_SEED,
+    error::EscrowError,
+    events::BasketRefunded,
+    instructions::make_basket::{check_leg_ata, load_leg_mint, load_leg_vault},
+    state::{BasketEscrow, EscrowStatus, Leg},
+    token_extensions::{harvest_vault_fees, transfer_checked},
+};
+
+// remaining_accounts, per offered leg: [mint, vault, maker_ata],
+// followed by the hook accounts of any transfer-hook mints
+#[derive(Accounts)]
+pub struct RefundBasketAccounts<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        close=maker,
+        has_one=maker,
+        seeds=[
+            BASKET_SEED,
+            basket.seed.to_le_bytes().as_ref(),
+            maker.key().as_ref()],
+        bump=basket.bump
+    )]
+    pub basket: Account<'info, BasketEscrow>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RefundBasketAccounts<'info> {
+    pub fn refund_basket_handler(
+        &mut self,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        require!(
+            self.basket.status == EscrowStatus::Deposited,
+            EscrowError::InvalidStatus
+        );
+        let offered = self.basket.offered.clone();
+        let (leg_accounts, _) = remaining_accounts
+            .split_at_checked(offered.len() * 3)
+            .ok_or(EscrowError::InvalidLegAccount)?;
+
+        let mut returned = Vec::with_capacity(offered.len());
+        for (leg, accounts) in offered.iter().zip(leg_accounts.chunks(3)) {
+            let [mint, vault, maker_ata] = accounts else {
+                return err!(EscrowError::InvalidLegAccount);
+            };
+            let amount = self.refund_leg(leg, mint, vault, maker_ata, remaining_accounts)?;
+            returned.push(Leg {
+                mint: leg.mint,
+                amount,
+            });
+        }
+
+        self.basket.status = EscrowStatus::Refunded;
+
+        emit!(BasketRefunded {
+            basket: self.basket.key(),
+            maker: self.maker.key(),
+            offered: returned,
+            seed: self.basket.seed,
+            slot: Clock::get()?.slot,
+        });
+        Ok(())
+    }
+
+    // transfer the leg from its vault back to the maker ATA and close the vault,
+    // returning the amount sent
+    fn refund_leg(
+        &self,
+        leg: &Leg,
+        mint: &AccountInfo<'info>,
+        vault: &AccountInfo<'info>,
+        maker_ata: &AccountInfo<'info>,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<u64> {
+        let token_program = self.token_program.key();
+        let decimals = load_leg_mint(leg, mint, &token_program)?.decimals;
+        check_leg_ata(vault, &self.basket.key(), leg, &token_program)?;
+        check_leg_ata(maker_ata, &self.maker.key(), leg, &token_program)?;
+        let amount = load_leg_vault(vault)?.amount;
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            BASKET_SEED,
+            &self.basket.seed.to_le_bytes(),
+            self.maker.key.as_ref(),
+            &[self.basket.bump],
+        ]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            TransferChecked {
+                from: vault.clone(),
+                mint: mint.clone(),
+                to: maker_ata.clone(),
+                authority: self.basket.to_account_info(),
+            },
+            signer_seeds,
+        )
+        .with_remaining_accounts(remaining_accounts.to_vec());
+        transfer_checked(cpi_ctx, amount, decimals)?;
+
+        harvest_vault_fees(&self.token_program, mint, vault)?;
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: vault.clone(),
+                destination: self.maker.to_account_info(),
+                authority: self.basket.to_account_info(),
+            },
+            signer_seeds,
+        );
+        close_account(cpi_ctx)?;
+        Ok(amount)
+    }
+}
@@ -2,15 +2,16 @@ use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
     token_interface::{
-        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
-        TransferChecked,
+        close_account, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
     },
 };
 
 use crate::{
-    constants::ESCROW_SEED,
+    constants::{CONFIG_SEED, ESCROW_SEED},
     error::EscrowError,
-    state::{Escrow, EscrowStatus},
+    events::EscrowTaken,
+    state::{Config, Escrow, EscrowStatus},
+    token_extensions::{gross_up, harvest_vault_fees, transfer_checked},
 };
 
 #[derive(Accounts)]
@@ -23,7 +24,6 @@ pub struct TakerAccounts<'info> {
 
     #[account(
        mut,
-       close=maker,
        has_one=mint_a,
        has_one=mint_b,
        has_one=maker,
@@ -35,8 +35,19 @@ pub struct TakerAccounts<'info> {
     )]
     pub escrow: Account<'info, Escrow>,
 
+    #[account(
+        seeds=[CONFIG_SEED],
+        bump=config.bump,
+        has_one=treasury
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    pub treasury: SystemAccount<'info>,
+
     // Token mint for the asset taker receives (Token A).
+    // Writable so withheld transfer fees can be harvested before the vault closes
     #[account(
+        mut,
         mint::token_program=token_program
     )]
     pub mint_a: InterfaceAccount<'info, Mint>,
@@ -85,29 +96,118 @@ pub struct TakerAccounts<'info> {
     )]
     pub maker_ata_b: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    /// Treasury's token account to receive the protocol fee in Token B.
+    #[account(
+        init_if_needed,
+        payer=taker,
+        associated_token::mint=mint_b,
+        associated_token::authority=treasury,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_ata_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> TakerAccounts<'info> {
-    pub fn taker_handler(&mut self) -> Result<()> {
+    pub fn taker_handler(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let amount_b = self.escrow.remaining_receive();
+        self.take_partial_handler(amount_b, remaining_accounts)
+    }
+
+    pub fn take_partial_handler(
+        &mut self,
+        amount_b: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
         require!(
-            self.escrow.status == EscrowStatus::Deposited,
+            self.escrow.status == EscrowStatus::Deposited
+                || self.escrow.status == EscrowStatus::PartiallyFilled,
             EscrowError::InvalidStatus
         );
+        require!(
+            !self.escrow.is_expired(Clock::get()?.unix_timestamp),
+            EscrowError::EscrowExpired
+        );
+        require!(
+            self.escrow.can_take(self.taker.key),
+            EscrowError::UnauthorizedTaker
+        );
+        require!(amount_b > 0, EscrowError::InvalidAmount);
+        require!(
+            amount_b <= self.escrow.remaining_receive(),
+            EscrowError::FillTooLarge
+        );
+        let amount_a = self
+            .escrow
+            .fill_amount(amount_b)
+            .ok_or(EscrowError::MathOverflow)?;
+        require!(amount_a > 0, EscrowError::FillTooSmall);
+        // The final fill sweeps the whole vault, including any tokens donated
+        // to it, so the vault is empty when it is closed
+        let final_fill = amount_b == self.escrow.remaining_receive();
+        let amount_out = if final_fill {
+            self.vault.amount
+        } else {
+            amount_a
+        };
+
         // 1. Transfer payment tokens from taker to maker
-        self.transfer_payment()?;
-        // 2. Transfer NFT from vault to taker (requires PDA seeds)
-        self.transfer_nft()?;
+        self.transfer_payment(amount_b, remaining_accounts)?;
+        // 2. Transfer Token A from vault to taker (requires PDA seeds)
+        self.transfer_nft(amount_out, remaining_accounts)?;
+
+        self.escrow.filled_amount = self
+            .escrow
+            .filled_amount
+            .checked_add(amount_b)
+            .ok_or(EscrowError::MathOverflow)?;
+        self.escrow.remaining_amount = self
+            .escrow
+            .remaining_amount
+            .checked_sub(amount_a)
+            .ok_or(EscrowError::MathOverflow)?;
+
+        emit!(EscrowTaken {
+            escrow: self.escrow.key(),
+            maker: self.maker.key(),
+            taker: self.taker.key(),
+            mint_a: self.mint_a.key(),
+            mint_b: self.mint_b.key(),
+            amount_a: amount_out,
+            amount_b,
+            remaining_amount: self.escrow.remaining_amount,
+            seed: self.escrow.seed,
+            slot: Clock::get()?.slot,
+        });
+
+        if !final_fill {
+            self.escrow.status = EscrowStatus::PartiallyFilled;
+            return Ok(());
+        }
+
         // 3. Close vault account and send rent to maker
         self.close_vault()?;
-        // 4. Update status
+        // 4. Update status and close escrow
         self.escrow.status = EscrowStatus::Completed;
-        Ok(())
+        self.escrow.close(self.maker.to_account_info())
     }
 
-    fn transfer_payment(&mut self) -> Result<()> {
+    // The maker and treasury each receive their full share; any Token B
+    // transfer fee is paid on top by the taker
+    fn transfer_payment(
+        &mut self,
+        amount: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let fee = self
+            .config
+            .fee_amount(amount)
+            .ok_or(EscrowError::MathOverflow)?;
+        let maker_amount = amount.checked_sub(fee).ok_or(EscrowError::MathOverflow)?;
+
         let cpi_ctx = CpiContext::new(
             self.token_program.to_account_info(),
             TransferChecked {
@@ -116,15 +216,33 @@ impl<'info> TakerAccounts<'info> {
                 to: self.maker_ata_b.to_account_info(),
                 authority: self.taker.to_account_info(),
             },
-        );
-        transfer_checked(cpi_ctx, self.escrow.receive_amount, self.mint_b.decimals)
+        )
+        .with_remaining_accounts(remaining_accounts.to_vec());
+        let maker_amount = gross_up(&self.mint_b, maker_amount)?;
+        transfer_checked(cpi_ctx, maker_amount, self.mint_b.decimals)?;
+
+        if fee > 0 {
+            let cpi_ctx = CpiContext::new(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.taker_ata_b.to_account_info(),
+                    mint: self.mint_b.to_account_info(),
+                    to: self.treasury_ata_b.to_account_info(),
+                    authority: self.taker.to_account_info(),
+                },
+            )
+            .with_remaining_accounts(remaining_accounts.to_vec());
+            let fee = gross_up(&self.mint_b, fee)?;
+            transfer_checked(cpi_ctx, fee, self.mint_b.decimals)?;
+        }
+        Ok(())
     }
 
-    fn transfer_nft(&mut self) -> Result<()> {
-        require!(
-            self.escrow.status == EscrowStatus::Deposited,
-            EscrowError::InvalidStatus
-        );
+    fn transfer_nft(
+        &mut self,
+        amount: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
         let signer_seeds: &[&[&[u8]]] = &[&[
             ESCROW_SEED,
             &self.escrow.seed.to_le_bytes(),
@@ -140,11 +258,13 @@ impl<'info> TakerAccounts<'info> {
                 authority: self.escrow.to_account_info(),
             },
             signer_seeds,
-        );
-        transfer_checked(cpi_ctx, self.vault.amount, self.mint_a.decimals) // NFT has 0 decimals
+        )
+        .with_remaining_accounts(remaining_accounts.to_vec());
+        transfer_checked(cpi_ctx, amount, self.mint_a.decimals)
     }
 
     fn close_vault(&self) -> Result<()> {
+        harvest_vault_fees(&self.token_program, &self.mint_a, &self.vault)?;
         let signer_seeds: &[&[&[u8]]] = &[&[
             ESCROW_SEED,
             &self.escrow.seed.to_le_bytes(),
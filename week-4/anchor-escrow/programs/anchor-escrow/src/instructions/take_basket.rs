@@ -0,0 +1,255 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{create_idempotent, AssociatedToken, Create},
+    token_interface::{close_account, CloseAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::{BASKET_SEED, CONFIG_SEED},
+    error::EscrowError,
+    events::BasketTaken,
+    instructions::make_basket::{check_leg_ata, load_leg_mint, load_leg_vault},
+    state::{BasketEscrow, Config, EscrowStatus, Leg},
+    token_extensions::{gross_up, harvest_vault_fees, transfer_checked},
+};
+
+// remaining_accounts, per offered leg:   [mint, vault, taker_ata]
+//                     per requested leg: [mint, taker_ata, maker_ata, treasury_ata]
+//                     then the hook accounts of any transfer-hook mints
+#[derive(Accounts)]
+pub struct TakeBasketAccounts<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close=maker,
+        has_one=maker,
+        seeds=[
+            BASKET_SEED,
+            basket.seed.to_le_bytes().as_ref(),
+            maker.key().as_ref()],
+        bump=basket.bump
+    )]
+    pub basket: Account<'info, BasketEscrow>,
+
+    #[account(
+        seeds=[CONFIG_SEED],
+        bump=config.bump,
+        has_one=treasury
+    )]
+    pub config: Account<'info, Config>,
+
+    pub treasury: SystemAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> TakeBasketAccounts<'info> {
+    // Every leg settles in this one instruction, so any failing leg reverts the whole trade
+    pub fn take_basket_handler(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        require!(
+            self.basket.status == EscrowStatus::Deposited,
+            EscrowError::InvalidStatus
+        );
+        let offered = self.basket.offered.clone();
+        let requested = self.basket.requested.clone();
+        let (offered_accounts, rest) = remaining_accounts
+            .split_at_checked(offered.len() * 3)
+            .ok_or(EscrowError::InvalidLegAccount)?;
+        let (requested_accounts, _) = rest
+            .split_at_checked(requested.len() * 4)
+            .ok_or(EscrowError::InvalidLegAccount)?;
+
+        // 1. Pay every requested leg from taker to maker (minus protocol fee)
+        for (leg, accounts) in requested.iter().zip(requested_accounts.chunks(4)) {
+            let [mint, taker_ata, maker_ata, treasury_ata] = accounts else {
+                return err!(EscrowError::InvalidLegAccount);
+            };
+            self.pay_leg(
+                leg,
+                mint,
+                taker_ata,
+                maker_ata,
+                treasury_ata,
+                remaining_accounts,
+            )?;
+        }
+
+        // 2. Release every offered leg from its vault to the taker and close the vault
+        let mut released = Vec::with_capacity(offered.len());
+        for (leg, accounts) in offered.iter().zip(offered_accounts.chunks(3)) {
+            let [mint, vault, taker_ata] = accounts else {
+                return err!(EscrowError::InvalidLegAccount);
+            };
+            let amount = self.release_leg(leg, mint, vault, taker_ata, remaining_accounts)?;
+            released.push(Leg {
+                mint: leg.mint,
+                amount,
+            });
+        }
+
+        self.basket.status = EscrowStatus::Completed;
+
+        emit!(BasketTaken {
+            basket: self.basket.key(),
+            maker: self.maker.key(),
+            taker: self.taker.key(),
+            offered: released,
+            requested,
+            seed: self.basket.seed,
+            slot: Clock::get()?.slot,
+        });
+        Ok(())
+    }
+
+    // The maker and treasury each receive their full share; any transfer fee is
+    // paid on top by the taker. Extensions were already checked in `make_basket`.
+    fn pay_leg(
+        &self,
+        leg: &Leg,
+        mint: &AccountInfo<'info>,
+        taker_ata: &AccountInfo<'info>,
+        maker_ata: &AccountInfo<'info>,
+        treasury_ata: &AccountInfo<'info>,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let token_program = self.token_program.key();
+        let decimals = load_leg_mint(leg, mint, &token_program)?.decimals;
+        check_leg_ata(taker_ata, &self.taker.key(), leg, &token_program)?;
+        check_leg_ata(maker_ata, &self.maker.key(), leg, &token_program)?;
+        check_leg_ata(treasury_ata, &self.treasury.key(), leg, &token_program)?;
+
+        let fee = self
+            .config
+            .fee_amount(leg.amount)
+            .ok_or(EscrowError::MathOverflow)?;
+        let maker_amount = leg
+            .amount
+            .checked_sub(fee)
+            .ok_or(EscrowError::MathOverflow)?;
+
+        self.create_ata(maker_ata, &self.maker.to_account_info(), mint)?;
+        let maker_amount = gross_up(mint, maker_amount)?;
+        self.transfer_from_taker(
+            taker_ata,
+            mint,
+            maker_ata,
+            maker_amount,
+            decimals,
+            remaining_accounts,
+        )?;
+
+        if fee > 0 {
+            self.create_ata(treasury_ata, &self.treasury.to_account_info(), mint)?;
+            let fee = gross_up(mint, fee)?;
+            self.transfer_from_taker(
+                taker_ata,
+                mint,
+                treasury_ata,
+                fee,
+                decimals,
+                remaining_accounts,
+            )?;
+        }
+        Ok(())
+    }
+
+    // Sends the whole vault balance, donations included, so the vault can always be closed.
+    // Returns the amount sent.
+    fn release_leg(
+        &self,
+        leg: &Leg,
+        mint: &AccountInfo<'info>,
+        vault: &AccountInfo<'info>,
+        taker_ata: &AccountInfo<'info>,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<u64> {
+        let token_program = self.token_program.key();
+        let decimals = load_leg_mint(leg, mint, &token_program)?.decimals;
+        check_leg_ata(vault, &self.basket.key(), leg, &token_program)?;
+        check_leg_ata(taker_ata, &self.taker.key(), leg, &token_program)?;
+        let amount = load_leg_vault(vault)?.amount;
+
+        self.create_ata(taker_ata, &self.taker.to_account_info(), mint)?;
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            BASKET_SEED,
+            &self.basket.seed.to_le_bytes(),
+            self.maker.key.as_ref(),
+            &[self.basket.bump],
+        ]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            TransferChecked {
+                from: vault.clone(),
+                mint: mint.clone(),
+                to: taker_ata.clone(),
+                authority: self.basket.to_account_info(),
+            },
+            signer_seeds,
+        )
+        .with_remaining_accounts(remaining_accounts.to_vec());
+        transfer_checked(cpi_ctx, amount, decimals)?;
+
+        harvest_vault_fees(&self.token_program, mint, vault)?;
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: vault.clone(),
+                destination: self.maker.to_account_info(),
+                authority: self.basket.to_account_info(),
+            },
+            signer_seeds,
+        );
+        close_account(cpi_ctx)?;
+        Ok(amount)
+    }
+
+    fn create_ata(
+        &self,
+        ata: &AccountInfo<'info>,
+        authority: &AccountInfo<'info>,
+        mint: &AccountInfo<'info>,
+    ) -> Result<()> {
+        create_idempotent(CpiContext::new(
+            self.associated_token_program.to_account_info(),
+            Create {
+                payer: self.taker.to_account_info(),
+                associated_token: ata.clone(),
+                authority: authority.clone(),
+                mint: mint.clone(),
+                system_program: self.system_program.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+            },
+        ))
+    }
+
+    fn transfer_from_taker(
+        &self,
+        from: &AccountInfo<'info>,
+        mint: &AccountInfo<'info>,
+        to: &AccountInfo<'info>,
+        amount: u64,
+        decimals: u8,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let cpi_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            TransferChecked {
+                from: from.clone(),
+                mint: mint.clone(),
+                to: to.clone(),
+                authority: self.taker.to_account_info(),
+            },
+        )
+        .with_remaining_accounts(remaining_accounts.to_vec());
+        transfer_checked(cpi_ctx, amount, decimals)
+    }
+}
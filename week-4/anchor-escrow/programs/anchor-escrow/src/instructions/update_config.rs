@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{CONFIG_SEED, MAX_FEE_BPS},
+    error::EscrowError,
+    state::Config,
+};
+
+#[derive(Accounts)]
+pub struct UpdateConfigAccounts<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[CONFIG_SEED],
+        bump=config.bump,
+        has_one=admin
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl<'info> UpdateConfigAccounts<'info> {
+    pub fn update_config_handler(&mut self, fee_bps: u16, treasury: Pubkey) -> Result<()> {
+        require!(fee_bps <= MAX_FEE_BPS, EscrowError::FeeTooHigh);
+
+        self.config.fee_bps = fee_bps;
+        self.config.treasury = treasury;
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{
+    constants::ESCROW_SEED,
+    error::EscrowError,
+    events::EscrowUpdated,
+    state::{Escrow, EscrowStatus},
+    token_extensions::{check_mint_extensions, gross_up, transfer_checked},
+};
+
+#[derive(Accounts)]
+pub struct UpdateOfferAccounts<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[
+            ESCROW_SEED,
+            escrow.seed.to_le_bytes().as_ref(),
+            maker.key().as_ref()],
+        bump=escrow.bump,
+        has_one=mint_a,
+        has_one=maker
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(mint::token_program=token_program)]
+    pub mint_a: InterfaceAccount<'info, Mint>,
+
+    // Replacement for Token B, only passed when the maker changes what they ask for
+    #[account(mint::token_program=token_program)]
+    pub new_mint_b: Option<InterfaceAccount<'info, Mint>>,
+
+    // Vault: Associated Token Account owned by Escrow PDA
+    #[account(
+        mut,
+        associated_token::mint=mint_a,
+        associated_token::authority=escrow,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    // Maker's token account for mint A
+    #[account(
+        mut,
+        associated_token::mint=mint_a,
+        associated_token::authority=maker,
+        associated_token::token_program = token_program
+    )]
+    pub maker_ata_a: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> UpdateOfferAccounts<'info> {
+    pub fn update_offer_handler(
+        &mut self,
+        receive_amount: Option<u64>,
+        deposit_amount: Option<u64>,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        // Only untouched offers can change, so no taker has traded on the old terms
+        require!(
+            self.escrow.status == EscrowStatus::Deposited,
+            EscrowError::InvalidStatus
+        );
+        let old_receive_amount = self.escrow.receive_amount;
+        let old_deposit_amount = self.escrow.deposit_amount;
+        let old_mint_b = self.escrow.mint_b;
+
+        if let Some(receive_amount) = receive_amount {
+            require!(receive_amount > 0, EscrowError::InvalidAmount);
+            self.escrow.receive_amount = receive_amount;
+        }
+        if let Some(deposit_amount) = deposit_amount {
+            self.update_deposit(deposit_amount, remaining_accounts)?;
+        }
+        if let Some(new_mint_b) = &self.new_mint_b {
+            check_mint_extensions(new_mint_b)?;
+            self.escrow.mint_b = new_mint_b.key();
+        }
+
+        emit!(EscrowUpdated {
+            escrow: self.escrow.key(),
+            maker: self.maker.key(),
+            seed: self.escrow.seed,
+            old_receive_amount,
+            receive_amount: self.escrow.receive_amount,
+            old_deposit_amount,
+            deposit_amount: self.escrow.deposit_amount,
+            old_mint_b,
+            mint_b: self.escrow.mint_b,
+            slot: Clock::get()?.slot,
+        });
+        Ok(())
+    }
+
+    // Tops up or withdraws part of the vault so it holds `deposit_amount`
+    fn update_deposit(
+        &mut self,
+        deposit_amount: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        require!(deposit_amount > 0, EscrowError::InvalidAmount);
+        let current = self.escrow.deposit_amount;
+
+        if deposit_amount > current {
+            let cpi_ctx = CpiContext::new(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.maker_ata_a.to_account_info(),
+                    mint: self.mint_a.to_account_info(),
+                    to: self.vault.to_account_info(),
+                    authority: self.maker.to_account_info(),
+                },
+            )
+            .with_remaining_accounts(remaining_accounts.to_vec());
+            let amount = gross_up(&self.mint_a, deposit_amount - current)?;
+            transfer_checked(cpi_ctx, amount, self.mint_a.decimals)?;
+        } else if deposit_amount < current {
+            let signer_seeds: &[&[&[u8]]] = &[&[
+                ESCROW_SEED,
+                &self.escrow.seed.to_le_bytes(),
+                self.maker.key.as_ref(),
+                &[self.escrow.bump],
+            ]];
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.vault.to_account_info(),
+                    mint: self.mint_a.to_account_info(),
+                    to: self.maker_ata_a.to_account_info(),
+                    authority: self.escrow.to_account_info(),
+                },
+                signer_seeds,
+            )
+            .with_remaining_accounts(remaining_accounts.to_vec());
+            transfer_checked(cpi_ctx, current - deposit_amount, self.mint_a.decimals)?;
+        }
+
+        self.escrow.deposit_amount = deposit_amount;
+        self.escrow.remaining_amount = deposit_amount;
+        Ok(())
+    }
+}
@@ -1,30 +1,127 @@
 use anchor_lang::prelude::*;
 
 declare_id!("Tw1wVuYavjQm3zET6wCZh7W6kUHnBMnvGzy7e7NpiKZ");
-mod constants;
-mod error;
+pub mod constants;
+pub mod error;
+pub mod events;
 mod instructions;
-mod state;
-use crate::instructions::*;
+pub mod state;
+mod token_extensions;
+use crate::{instructions::*, state::Leg};
 
 #[program]
 pub mod anchor_escrow {
 
     use super::*;
 
-    pub fn make(ctx: Context<MakerAccounts>, seed: u64, receive: u64, deposit: u64) -> Result<()> {
+    pub fn make<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MakerAccounts<'info>>,
+        seed: u64,
+        receive: u64,
+        deposit: u64,
+        expires_at: i64,
+        allowed_taker: Option<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts.make_handler(
+            seed,
+            receive,
+            deposit,
+            expires_at,
+            allowed_taker,
+            &ctx.bumps,
+            ctx.remaining_accounts,
+        )?;
+        Ok(())
+    }
+
+    pub fn take<'info>(ctx: Context<'_, '_, 'info, 'info, TakerAccounts<'info>>) -> Result<()> {
+        ctx.accounts.taker_handler(ctx.remaining_accounts)?;
+        Ok(())
+    }
+
+    pub fn take_partial<'info>(
+        ctx: Context<'_, '_, 'info, 'info, TakerAccounts<'info>>,
+        amount_b: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .take_partial_handler(amount_b, ctx.remaining_accounts)?;
+        Ok(())
+    }
+
+    pub fn refund<'info>(ctx: Context<'_, '_, 'info, 'info, RefundAccounts<'info>>) -> Result<()> {
+        ctx.accounts.refund_handler(ctx.remaining_accounts)?;
+        Ok(())
+    }
+
+    pub fn update_offer<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdateOfferAccounts<'info>>,
+        receive_amount: Option<u64>,
+        deposit_amount: Option<u64>,
+    ) -> Result<()> {
+        ctx.accounts.update_offer_handler(
+            receive_amount,
+            deposit_amount,
+            ctx.remaining_accounts,
+        )?;
+        Ok(())
+    }
+
+    pub fn migrate_escrow(ctx: Context<MigrateEscrowAccounts>) -> Result<()> {
+        ctx.accounts.migrate_escrow_handler()?;
+        Ok(())
+    }
+
+    pub fn make_basket<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MakeBasketAccounts<'info>>,
+        seed: u64,
+        offered: Vec<Leg>,
+        requested: Vec<Leg>,
+    ) -> Result<()> {
+        ctx.accounts.make_basket_handler(
+            seed,
+            offered,
+            requested,
+            &ctx.bumps,
+            ctx.remaining_accounts,
+        )?;
+        Ok(())
+    }
+
+    pub fn take_basket<'info>(
+        ctx: Context<'_, '_, 'info, 'info, TakeBasketAccounts<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.take_basket_handler(ctx.remaining_accounts)?;
+        Ok(())
+    }
+
+    pub fn refund_basket<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RefundBasketAccounts<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.refund_basket_handler(ctx.remaining_accounts)?;
+        Ok(())
+    }
+
+    pub fn initialize_config(
+        ctx: Context<InitializeConfigAccounts>,
+        fee_bps: u16,
+        treasury: Pubkey,
+    ) -> Result<()> {
         ctx.accounts
-            .make_handler(seed, receive, deposit, &ctx.bumps)?;
+            .initialize_config_handler(fee_bps, treasury, &ctx.bumps)?;
         Ok(())
     }
 
-    pub fn take(ctx: Context<TakerAccounts>) -> Result<()> {
-        ctx.accounts.taker_handler()?;
+    pub fn update_config(
+        ctx: Context<UpdateConfigAccounts>,
+        fee_bps: u16,
+        treasury: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.update_config_handler(fee_bps, treasury)?;
         Ok(())
     }
 
-    pub fn refund(ctx: Context<RefundAccounts>) -> Result<()> {
-        ctx.accounts.refund_handler()?;
+    pub fn expire<'info>(ctx: Context<'_, '_, 'info, 'info, ExpireAccounts<'info>>) -> Result<()> {
+        ctx.accounts.expire_handler(ctx.remaining_accounts)?;
         Ok(())
     }
 }
@@ -1,23 +1,111 @@
 use anchor_lang::prelude::*;
 
+use crate::constants::{BPS_DENOMINATOR, ESCROW_RESERVED_LEN, MAX_BASKET_LEGS};
+
+#[derive(InitSpace)]
 #[account]
 pub struct Escrow {
+    pub version: u8,
     pub maker: Pubkey,
     pub mint_a: Pubkey,
     pub mint_b: Pubkey,
     pub receive_amount: u64,
+    pub deposit_amount: u64,
+    pub remaining_amount: u64,
+    pub filled_amount: u64,
+    pub expires_at: i64,
+    pub allowed_taker: Option<Pubkey>,
     pub seed: u64,
     pub bump: u8,
     pub status: EscrowStatus,
+    // Spare bytes so future fields don't require a realloc
+    pub reserved: [u8; ESCROW_RESERVED_LEN],
 }
 impl Escrow {
-    pub const LEN: usize = 32 + 32 + 32 + 8 + 8 + 1 + 1;
+    // Token B still owed for the rest of the deposit
+    pub fn remaining_receive(&self) -> u64 {
+        self.receive_amount - self.filled_amount
+    }
+
+    // Pro-rata share of the remaining Token A for a payment of `amount_b`.
+    // Rounds down so the taker never receives more than they paid for.
+    pub fn fill_amount(&self, amount_b: u64) -> Option<u64> {
+        let amount_a = (amount_b as u128)
+            .checked_mul(self.remaining_amount as u128)?
+            .checked_div(self.remaining_receive() as u128)?;
+        u64::try_from(amount_a).ok()
+    }
+
+    // An `expires_at` of 0 means the offer never expires
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at != 0 && now >= self.expires_at
+    }
+
+    // Open offers (`allowed_taker == None`) can be filled by anyone
+    pub fn can_take(&self, taker: &Pubkey) -> bool {
+        self.allowed_taker.is_none_or(|allowed| allowed == *taker)
+    }
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
 pub enum EscrowStatus {
-    Initialized = 0, // Escrow created, waiting for deposit
-    Deposited = 1,   // Tokens in vault, waiting for taker
-    Completed = 2,   // Trade completed successfully
-    Refunded = 3,    // Maker refunded, trade cancelled
+    Initialized = 0,     // Escrow created, waiting for deposit
+    Deposited = 1,       // Tokens in vault, waiting for taker
+    Completed = 2,       // Trade completed successfully
+    Refunded = 3,        // Maker refunded, trade cancelled
+    PartiallyFilled = 4, // Part of the deposit taken, rest still in vault
+    Expired = 5,         // Offer expired, deposit returned to maker
+}
+
+#[derive(InitSpace)]
+#[account]
+pub struct Config {
+    pub admin: Pubkey,
+    pub treasury: Pubkey,
+    pub fee_bps: u16,
+    pub bump: u8,
+}
+impl Config {
+    // Protocol fee on a Token B payment, rounded down in favour of the maker
+    pub fn fee_amount(&self, amount: u64) -> Option<u64> {
+        let fee = (amount as u128)
+            .checked_mul(self.fee_bps as u128)?
+            .checked_div(BPS_DENOMINATOR as u128)?;
+        u64::try_from(fee).ok()
+    }
+}
+
+// One (mint, amount) side of a basket trade
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct Leg {
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[derive(InitSpace)]
+#[account]
+pub struct BasketEscrow {
+    pub maker: Pubkey,
+    #[max_len(MAX_BASKET_LEGS)]
+    pub offered: Vec<Leg>,
+    #[max_len(MAX_BASKET_LEGS)]
+    pub requested: Vec<Leg>,
+    pub seed: u64,
+    pub bump: u8,
+    pub status: EscrowStatus,
+}
+
+// Layout of `Escrow` before accounts were versioned, kept so `migrate_escrow` can read it
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct EscrowV0 {
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub receive_amount: u64,
+    pub seed: u64,
+    pub bump: u8,
+    pub status: EscrowStatus,
+}
+impl EscrowV0 {
+    pub const LEN: usize = 32 + 32 + 32 + 8 + 8 + 1 + 1;
 }
@@ -0,0 +1,127 @@
+use anchor_lang::{prelude::*, solana_program::program::invoke_signed};
+use anchor_spl::{
+    token_2022::spl_token_2022::{
+        self,
+        extension::{
+            transfer_fee::{TransferFee, TransferFeeConfig},
+            transfer_hook, BaseStateWithExtensions, StateWithExtensions,
+        },
+        state::Mint as MintState,
+    },
+    token_2022_extensions::{harvest_withheld_tokens_to_mint, HarvestWithheldTokensToMint},
+    token_interface::TransferChecked,
+};
+use spl_transfer_hook_interface::onchain::add_extra_accounts_for_execute_cpi;
+
+use crate::{constants::SUPPORTED_MINT_EXTENSIONS, error::EscrowError};
+
+// Rejects Token-2022 mints carrying an extension outside `SUPPORTED_MINT_EXTENSIONS`.
+// The helpers here take any mint account, so basket legs can pass a raw `AccountInfo`.
+pub(crate) fn check_mint_extensions<'info>(mint: &impl ToAccountInfo<'info>) -> Result<()> {
+    let info = mint.to_account_info();
+    if *info.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+    let data = info.try_borrow_data()?;
+    let state = StateWithExtensions::<MintState>::unpack(&data)?;
+    for extension in state.get_extension_types()? {
+        require!(
+            SUPPORTED_MINT_EXTENSIONS.contains(&extension),
+            EscrowError::UnsupportedMintExtension
+        );
+    }
+    Ok(())
+}
+
+// Transfer fee the mint charges this epoch, if it has one
+fn epoch_transfer_fee<'info>(mint: &impl ToAccountInfo<'info>) -> Result<Option<TransferFee>> {
+    let info = mint.to_account_info();
+    if *info.owner != spl_token_2022::ID {
+        return Ok(None);
+    }
+    let data = info.try_borrow_data()?;
+    let state = StateWithExtensions::<MintState>::unpack(&data)?;
+    let Ok(config) = state.get_extension::<TransferFeeConfig>() else {
+        return Ok(None);
+    };
+    Ok(Some(*config.get_epoch_fee(Clock::get()?.epoch)))
+}
+
+// Amount to send so the recipient is left with `amount` after the mint's transfer fee
+pub(crate) fn gross_up<'info>(mint: &impl ToAccountInfo<'info>, amount: u64) -> Result<u64> {
+    let Some(transfer_fee) = epoch_transfer_fee(mint)? else {
+        return Ok(amount);
+    };
+    let fee = transfer_fee
+        .calculate_inverse_fee(amount)
+        .ok_or(EscrowError::MathOverflow)?;
+    Ok(amount.checked_add(fee).ok_or(EscrowError::MathOverflow)?)
+}
+
+// Token-2022 won't close an account holding withheld fees, so sweep them to the mint first
+pub(crate) fn harvest_vault_fees<'info>(
+    token_program: &impl ToAccountInfo<'info>,
+    mint: &impl ToAccountInfo<'info>,
+    vault: &impl ToAccountInfo<'info>,
+) -> Result<()> {
+    if epoch_transfer_fee(mint)?.is_none() {
+        return Ok(());
+    }
+    let cpi_ctx = CpiContext::new(
+        token_program.to_account_info(),
+        HarvestWithheldTokensToMint {
+            token_program_id: token_program.to_account_info(),
+            mint: mint.to_account_info(),
+        },
+    );
+    harvest_withheld_tokens_to_mint(cpi_ctx, vec![vault.to_account_info()])
+}
+
+// Same as `token_interface::transfer_checked`, but when the mint has a transfer
+// hook the hook program, its validation account and any extra accounts it lists
+// are picked out of `ctx.remaining_accounts` and forwarded to Token-2022.
+pub(crate) fn transfer_checked<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, TransferChecked<'info>>,
+    amount: u64,
+    decimals: u8,
+) -> Result<()> {
+    let TransferChecked {
+        from,
+        mint,
+        to,
+        authority,
+    } = ctx.accounts;
+    let mut ix = spl_token_2022::instruction::transfer_checked(
+        ctx.program.key,
+        from.key,
+        mint.key,
+        to.key,
+        authority.key,
+        &[],
+        amount,
+        decimals,
+    )?;
+    let mut account_infos = vec![from.clone(), mint.clone(), to.clone(), authority.clone()];
+
+    let hook_program = if *mint.owner == spl_token_2022::ID {
+        let data = mint.try_borrow_data()?;
+        transfer_hook::get_program_id(&StateWithExtensions::<MintState>::unpack(&data)?)
+    } else {
+        None
+    };
+    if let Some(program_id) = hook_program {
+        add_extra_accounts_for_execute_cpi(
+            &mut ix,
+            &mut account_infos,
+            &program_id,
+            from,
+            mint,
+            to,
+            authority,
+            amount,
+            &ctx.remaining_accounts,
+        )?;
+    }
+
+    invoke_signed(&ix, &account_infos, ctx.signer_seeds).map_err(Into::into)
+}
@@ -0,0 +1,930 @@
+mod common;
+
+use anchor_escrow::{
+    accounts,
+    constants::*,
+    error::EscrowError,
+    instruction,
+    state::{BasketEscrow, Config, Escrow, EscrowStatus, EscrowV0, Leg},
+    ID as PROGRAM_ID,
+};
+use anchor_lang::{
+    error::ErrorCode,
+    prelude::*,
+    solana_program::{instruction::Instruction, system_program},
+    Discriminator,
+};
+use anchor_spl::{
+    associated_token::{self, get_associated_token_address_with_program_id},
+    token::spl_token,
+    token_2022::spl_token_2022::{
+        self,
+        extension::{
+            transfer_fee::instruction::initialize_transfer_fee_config, transfer_hook, ExtensionType,
+        },
+        instruction::initialize_non_transferable_mint,
+    },
+};
+use common::{instruction as ix, program_data_address, Bank, LAMPORTS_PER_SOL};
+use solana_instruction::error::InstructionError;
+use solana_transaction_error::TransactionError;
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, state::ExtraAccountMetaList};
+use spl_transfer_hook_interface::{
+    get_extra_account_metas_address, instruction::ExecuteInstruction,
+};
+use transfer_hook_counter::Counter;
+
+const FEE_BPS: u16 = 100;
+const DEPOSIT: u64 = 10;
+const RECEIVE: u64 = 100_000_000;
+
+struct Fixture {
+    bank: Bank,
+    token_program: Pubkey,
+    maker: Pubkey,
+    taker: Pubkey,
+    treasury: Pubkey,
+    mint_a: Pubkey,
+    mint_b: Pubkey,
+}
+
+fn setup(token_program: Pubkey) -> Fixture {
+    let mut bank = Bank::new();
+    let maker = bank.wallet(10 * LAMPORTS_PER_SOL);
+    let taker = bank.wallet(10 * LAMPORTS_PER_SOL);
+    let treasury = Pubkey::new_unique();
+
+    let mint_a = bank.create_mint(&token_program, 0);
+    let mint_b = bank.create_mint(&token_program, 6);
+    bank.create_token_account(&mint_a, &maker, DEPOSIT, &token_program);
+    bank.create_token_account(&mint_b, &taker, 1_000 * RECEIVE, &token_program);
+
+    let mut fixture = Fixture {
+        bank,
+        token_program,
+        maker,
+        taker,
+        treasury,
+        mint_a,
+        mint_b,
+    };
+    fixture
+        .bank
+        .process(&fixture.initialize_config_ix(FEE_BPS))
+        .unwrap();
+    fixture
+}
+
+impl Fixture {
+    fn ata(&self, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(owner, mint, &self.token_program)
+    }
+
+    fn escrow(&self, seed: u64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[ESCROW_SEED, &seed.to_le_bytes(), self.maker.as_ref()],
+            &PROGRAM_ID,
+        )
+        .0
+    }
+
+    fn vault(&self, seed: u64) -> Pubkey {
+        self.ata(&self.escrow(seed), &self.mint_a)
+    }
+
+    fn config(&self) -> Pubkey {
+        Pubkey::find_program_address(&[CONFIG_SEED], &PROGRAM_ID).0
+    }
+
+    fn escrow_state(&self, seed: u64) -> Option<Escrow> {
+        self.bank.anchor_account(&self.escrow(seed))
+    }
+
+    fn initialize_config_ix(&self, fee_bps: u16) -> Instruction {
+        ix(
+            accounts::InitializeConfigAccounts {
+                admin: self.bank.upgrade_authority,
+                config: self.config(),
+                program: PROGRAM_ID,
+                program_data: program_data_address(),
+                system_program: system_program::ID,
+            },
+            instruction::InitializeConfig {
+                fee_bps,
+                treasury: self.treasury,
+            },
+        )
+    }
+
+    fn make_ix(
+        &self,
+        seed: u64,
+        receive: u64,
+        deposit: u64,
+        expires_at: i64,
+        allowed_taker: Option<Pubkey>,
+    ) -> Instruction {
+        ix(
+            accounts::MakerAccounts {
+                maker: self.maker,
+                escrow: self.escrow(seed),
+                mint_a: self.mint_a,
+                mint_b: self.mint_b,
+                vault: self.vault(seed),
+                maker_ata_a: self.ata(&self.maker, &self.mint_a),
+                token_program: self.token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::Make {
+                seed,
+                receive,
+                deposit,
+                expires_at,
+                allowed_taker,
+            },
+        )
+    }
+
+    fn make(&mut self, seed: u64) {
+        let make = self.make_ix(seed, RECEIVE, DEPOSIT, 0, None);
+        self.bank.process(&make).unwrap();
+    }
+
+    fn taker_accounts(&self, seed: u64, taker: Pubkey, mint_b: Pubkey) -> accounts::TakerAccounts {
+        accounts::TakerAccounts {
+            taker,
+            maker: self.maker,
+            escrow: self.escrow(seed),
+            config: self.config(),
+            treasury: self.treasury,
+            mint_a: self.mint_a,
+            mint_b,
+            vault: self.vault(seed),
+            taker_ata_a: self.ata(&taker, &self.mint_a),
+            taker_ata_b: self.ata(&taker, &mint_b),
+            maker_ata_b: self.ata(&self.maker, &mint_b),
+            treasury_ata_b: self.ata(&self.treasury, &mint_b),
+            token_program: self.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        }
+    }
+
+    fn take_ix(&self, seed: u64) -> Instruction {
+        ix(
+            self.taker_accounts(seed, self.taker, self.mint_b),
+            instruction::Take {},
+        )
+    }
+
+    fn take_partial_ix(&self, seed: u64, amount_b: u64) -> Instruction {
+        ix(
+            self.taker_accounts(seed, self.taker, self.mint_b),
+            instruction::TakePartial { amount_b },
+        )
+    }
+
+    fn refund_ix(&self, seed: u64, maker: Pubkey) -> Instruction {
+        ix(
+            accounts::RefundAccounts {
+                maker,
+                escrow: self.escrow(seed),
+                mint_a: self.mint_a,
+                vault: self.vault(seed),
+                maker_ata_a: self.ata(&maker, &self.mint_a),
+                token_program: self.token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::Refund {},
+        )
+    }
+
+    fn expire_ix(&self, seed: u64, caller: Pubkey) -> Instruction {
+        ix(
+            accounts::ExpireAccounts {
+                caller,
+                maker: self.maker,
+                escrow: self.escrow(seed),
+                mint_a: self.mint_a,
+                vault: self.vault(seed),
+                maker_ata_a: self.ata(&self.maker, &self.mint_a),
+                token_program: self.token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::Expire {},
+        )
+    }
+
+    fn update_offer_ix(&self, seed: u64, receive_amount: Option<u64>) -> Instruction {
+        ix(
+            accounts::UpdateOfferAccounts {
+                maker: self.maker,
+                escrow: self.escrow(seed),
+                mint_a: self.mint_a,
+                new_mint_b: None,
+                vault: self.vault(seed),
+                maker_ata_a: self.ata(&self.maker, &self.mint_a),
+                token_program: self.token_program,
+            },
+            instruction::UpdateOffer {
+                receive_amount,
+                deposit_amount: None,
+            },
+        )
+    }
+
+    fn basket(&self, seed: u64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[BASKET_SEED, &seed.to_le_bytes(), self.maker.as_ref()],
+            &PROGRAM_ID,
+        )
+        .0
+    }
+
+    fn make_basket_ix(&self, seed: u64, offered: Vec<Leg>, requested: Vec<Leg>) -> Instruction {
+        let mut make = ix(
+            accounts::MakeBasketAccounts {
+                maker: self.maker,
+                basket: self.basket(seed),
+                token_program: self.token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::MakeBasket {
+                seed,
+                offered: offered.clone(),
+                requested: requested.clone(),
+            },
+        );
+        for leg in &offered {
+            make.accounts.extend([
+                AccountMeta::new_readonly(leg.mint, false),
+                AccountMeta::new(self.ata(&self.maker, &leg.mint), false),
+                AccountMeta::new(self.ata(&self.basket(seed), &leg.mint), false),
+            ]);
+        }
+        for leg in &requested {
+            make.accounts
+                .push(AccountMeta::new_readonly(leg.mint, false));
+        }
+        make
+    }
+
+    // Offered mints are writable so withheld transfer fees can be harvested from the vaults
+    fn take_basket_ix(&self, seed: u64, offered: &[Leg], requested: &[Leg]) -> Instruction {
+        let mut take = ix(
+            accounts::TakeBasketAccounts {
+                taker: self.taker,
+                maker: self.maker,
+                basket: self.basket(seed),
+                config: self.config(),
+                treasury: self.treasury,
+                token_program: self.token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::TakeBasket {},
+        );
+        for leg in offered {
+            take.accounts.extend([
+                AccountMeta::new(leg.mint, false),
+                AccountMeta::new(self.ata(&self.basket(seed), &leg.mint), false),
+                AccountMeta::new(self.ata(&self.taker, &leg.mint), false),
+            ]);
+        }
+        for leg in requested {
+            take.accounts.extend([
+                AccountMeta::new_readonly(leg.mint, false),
+                AccountMeta::new(self.ata(&self.taker, &leg.mint), false),
+                AccountMeta::new(self.ata(&self.maker, &leg.mint), false),
+                AccountMeta::new(self.ata(&self.treasury, &leg.mint), false),
+            ]);
+        }
+        take
+    }
+}
+
+fn escrow_error(error: EscrowError) -> std::result::Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error.into()),
+    ))
+}
+
+fn anchor_error(error: ErrorCode) -> std::result::Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(error.into()),
+    ))
+}
+
+// 1% on every transfer, with no cap
+fn transfer_fee_mint(f: &mut Fixture, decimals: u8) -> Pubkey {
+    f.bank
+        .create_mint_with_extensions(decimals, &[ExtensionType::TransferFeeConfig], |mint| {
+            vec![initialize_transfer_fee_config(
+                &spl_token_2022::ID,
+                mint,
+                None,
+                None,
+                100,
+                u64::MAX,
+            )
+            .unwrap()]
+        })
+}
+
+#[test]
+fn make_moves_deposit_into_vault() {
+    let mut f = setup(spl_token::ID);
+    f.make(1);
+
+    let escrow = f.escrow_state(1).unwrap();
+    assert_eq!(escrow.version, ESCROW_VERSION);
+    assert_eq!(escrow.maker, f.maker);
+    assert_eq!(escrow.receive_amount, RECEIVE);
+    assert_eq!(escrow.deposit_amount, DEPOSIT);
+    assert_eq!(escrow.remaining_amount, DEPOSIT);
+    assert!(escrow.status == EscrowStatus::Deposited);
+    assert_eq!(f.bank.token_balance(&f.vault(1)), DEPOSIT);
+    assert_eq!(f.bank.token_balance(&f.ata(&f.maker, &f.mint_a)), 0);
+}
+
+#[test]
+fn take_pays_maker_minus_fee_and_closes_escrow() {
+    let mut f = setup(spl_token::ID);
+    f.make(1);
+    f.bank.process(&f.take_ix(1)).unwrap();
+
+    let fee = RECEIVE * FEE_BPS as u64 / 10_000;
+    assert_eq!(f.bank.token_balance(&f.ata(&f.taker, &f.mint_a)), DEPOSIT);
+    assert_eq!(
+        f.bank.token_balance(&f.ata(&f.maker, &f.mint_b)),
+        RECEIVE - fee
+    );
+    assert_eq!(f.bank.token_balance(&f.ata(&f.treasury, &f.mint_b)), fee);
+    assert!(f.bank.account(&f.escrow(1)).is_none());
+    assert!(f.bank.account(&f.vault(1)).is_none());
+}
+
+#[test]
+fn take_partial_rounds_down_and_tracks_remaining() {
+    let mut f = setup(spl_token::ID);
+    f.make(1);
+
+    // 45% of the price buys floor(4.5) = 4 of the 10 deposited
+    f.bank
+        .process(&f.take_partial_ix(1, RECEIVE * 45 / 100))
+        .unwrap();
+    let escrow = f.escrow_state(1).unwrap();
+    assert!(escrow.status == EscrowStatus::PartiallyFilled);
+    assert_eq!(escrow.remaining_amount, 6);
+    assert_eq!(escrow.filled_amount, RECEIVE * 45 / 100);
+    assert_eq!(f.bank.token_balance(&f.ata(&f.taker, &f.mint_a)), 4);
+
+    // `take` fills exactly what is left
+    f.bank.process(&f.take_ix(1)).unwrap();
+    assert_eq!(f.bank.token_balance(&f.ata(&f.taker, &f.mint_a)), DEPOSIT);
+    assert!(f.bank.account(&f.escrow(1)).is_none());
+}
+
+#[test]
+fn final_take_sweeps_tokens_donated_to_the_vault() {
+    let mut f = setup(spl_token::ID);
+    f.make(1);
+    f.bank
+        .process(&f.take_partial_ix(1, RECEIVE * 45 / 100))
+        .unwrap();
+
+    // Anyone can send Token A to the vault ATA; the last fill takes it too
+    let escrow = f.escrow(1);
+    f.bank
+        .create_token_account(&f.mint_a, &escrow, 3, &f.token_program);
+    f.bank.process(&f.take_ix(1)).unwrap();
+
+    assert_eq!(
+        f.bank.token_balance(&f.ata(&f.taker, &f.mint_a)),
+        DEPOSIT + 3
+    );
+    assert!(f.bank.account(&f.escrow(1)).is_none());
+    assert!(f.bank.account(&f.vault(1)).is_none());
+}
+
+#[test]
+fn refund_returns_deposit_and_closes_escrow() {
+    let mut f = setup(spl_token::ID);
+    f.make(1);
+    f.bank.process(&f.refund_ix(1, f.maker)).unwrap();
+
+    assert_eq!(f.bank.token_balance(&f.ata(&f.maker, &f.mint_a)), DEPOSIT);
+    assert!(f.bank.account(&f.escrow(1)).is_none());
+    assert!(f.bank.account(&f.vault(1)).is_none());
+}
+
+#[test]
+fn token_2022_mints_make_take_and_refund() {
+    let mut f = setup(spl_token_2022::ID);
+    f.make(1);
+    assert_eq!(f.bank.token_balance(&f.vault(1)), DEPOSIT);
+    f.bank.process(&f.take_ix(1)).unwrap();
+    assert_eq!(f.bank.token_balance(&f.ata(&f.taker, &f.mint_a)), DEPOSIT);
+
+    f.bank
+        .create_token_account(&f.mint_a, &f.maker, DEPOSIT, &spl_token_2022::ID);
+    f.make(2);
+    f.bank.process(&f.refund_ix(2, f.maker)).unwrap();
+    assert_eq!(f.bank.token_balance(&f.ata(&f.maker, &f.mint_a)), DEPOSIT);
+}
+
+#[test]
+fn make_rejects_unsupported_mint_extension() {
+    let mut f = setup(spl_token_2022::ID);
+    f.mint_a = f
+        .bank
+        .create_mint_with_extensions(0, &[ExtensionType::NonTransferable], |mint| {
+            vec![initialize_non_transferable_mint(&spl_token_2022::ID, mint).unwrap()]
+        });
+    f.bank
+        .create_token_account(&f.mint_a, &f.maker, DEPOSIT, &f.token_program);
+
+    let make = f.make_ix(1, RECEIVE, DEPOSIT, 0, None);
+    assert_eq!(
+        f.bank.process(&make),
+        escrow_error(EscrowError::UnsupportedMintExtension)
+    );
+}
+
+#[test]
+fn transfer_fees_are_paid_on_top() {
+    let mut f = setup(spl_token_2022::ID);
+    f.mint_a = transfer_fee_mint(&mut f, 0);
+    f.mint_b = transfer_fee_mint(&mut f, 6);
+    f.bank
+        .create_token_account(&f.mint_a, &f.maker, DEPOSIT + 1, &f.token_program);
+    f.bank
+        .create_token_account(&f.mint_b, &f.taker, 2 * RECEIVE, &f.token_program);
+
+    // The maker covers the fee so the vault holds the full deposit
+    f.make(1);
+    assert_eq!(f.bank.token_balance(&f.vault(1)), DEPOSIT);
+    assert_eq!(f.bank.token_balance(&f.ata(&f.maker, &f.mint_a)), 0);
+
+    // The taker covers the fee on Token B; the fee on Token A comes out of what they receive
+    f.bank.process(&f.take_ix(1)).unwrap();
+    let protocol_fee = RECEIVE * FEE_BPS as u64 / 10_000;
+    assert_eq!(
+        f.bank.token_balance(&f.ata(&f.maker, &f.mint_b)),
+        RECEIVE - protocol_fee
+    );
+    assert_eq!(
+        f.bank.token_balance(&f.ata(&f.treasury, &f.mint_b)),
+        protocol_fee
+    );
+    assert_eq!(
+        f.bank.token_balance(&f.ata(&f.taker, &f.mint_a)),
+        DEPOSIT - 1
+    );
+    // The fee withheld in the vault on deposit was harvested to the mint before it closed
+    assert_eq!(f.bank.harvested_fees(&f.mint_a), 1);
+    assert!(f.bank.account(&f.vault(1)).is_none());
+    assert!(f.bank.account(&f.escrow(1)).is_none());
+}
+
+#[test]
+fn make_reports_math_overflow() {
+    let mut f = setup(spl_token_2022::ID);
+    f.mint_a = transfer_fee_mint(&mut f, 0);
+    f.bank
+        .create_token_account(&f.mint_a, &f.maker, DEPOSIT, &f.token_program);
+
+    // Covering the transfer fee on top of the whole supply doesn't fit in a u64
+    let make = f.make_ix(1, RECEIVE, u64::MAX, 0, None);
+    assert_eq!(
+        f.bank.process(&make),
+        escrow_error(EscrowError::MathOverflow)
+    );
+}
+
+#[test]
+fn transfer_hook_accounts_are_forwarded() {
+    let mut f = setup(spl_token_2022::ID);
+    let hook_program = transfer_hook_counter::ID;
+    f.mint_a = f
+        .bank
+        .create_mint_with_extensions(0, &[ExtensionType::TransferHook], |mint| {
+            vec![transfer_hook::instruction::initialize(
+                &spl_token_2022::ID,
+                mint,
+                None,
+                Some(hook_program),
+            )
+            .unwrap()]
+        });
+    f.bank
+        .create_token_account(&f.mint_a, &f.maker, DEPOSIT, &f.token_program);
+
+    // The hook lists one extra account, a counter it bumps on every transfer
+    let counter = Pubkey::new_unique();
+    let mut data = Vec::new();
+    Counter { transfers: 0 }.try_serialize(&mut data).unwrap();
+    f.bank.set_account(counter, data, hook_program);
+
+    let validation = get_extra_account_metas_address(&f.mint_a, &hook_program);
+    let extra_metas = [ExtraAccountMeta::new_with_pubkey(&counter, false, true).unwrap()];
+    let mut data = vec![0; ExtraAccountMetaList::size_of(extra_metas.len()).unwrap()];
+    ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &extra_metas).unwrap();
+    f.bank.set_account(validation, data, hook_program);
+
+    let hook_accounts = [
+        AccountMeta::new_readonly(hook_program, false),
+        AccountMeta::new_readonly(validation, false),
+        AccountMeta::new(counter, false),
+    ];
+    let transfers = |f: &Fixture| {
+        f.bank
+            .anchor_account::<Counter>(&counter)
+            .unwrap()
+            .transfers
+    };
+
+    let mut make = f.make_ix(1, RECEIVE, DEPOSIT, 0, None);
+    assert!(f.bank.process(&make).is_err());
+    make.accounts.extend(hook_accounts.clone());
+    f.bank.process(&make).unwrap();
+    assert_eq!(f.bank.token_balance(&f.vault(1)), DEPOSIT);
+    assert_eq!(transfers(&f), 1);
+
+    let mut take = f.take_ix(1);
+    take.accounts.extend(hook_accounts);
+    f.bank.process(&take).unwrap();
+    assert_eq!(f.bank.token_balance(&f.ata(&f.taker, &f.mint_a)), DEPOSIT);
+    assert_eq!(transfers(&f), 2);
+}
+
+#[test]
+fn refund_rejects_wrong_maker() {
+    let mut f = setup(spl_token::ID);
+    f.make(1);
+    let impostor = f.bank.wallet(LAMPORTS_PER_SOL);
+    f.bank
+        .create_token_account(&f.mint_a, &impostor, 0, &f.token_program);
+
+    let result = f.bank.process(&f.refund_ix(1, impostor));
+    assert_eq!(result, anchor_error(ErrorCode::ConstraintSeeds));
+    assert_eq!(f.bank.token_balance(&f.vault(1)), DEPOSIT);
+}
+
+#[test]
+fn take_rejects_wrong_mint() {
+    let mut f = setup(spl_token::ID);
+    f.make(1);
+    let other_mint = f.bank.create_mint(&f.token_program, 6);
+    f.bank
+        .create_token_account(&other_mint, &f.taker, RECEIVE, &f.token_program);
+
+    let take = ix(
+        f.taker_accounts(1, f.taker, other_mint),
+        instruction::Take {},
+    );
+    assert_eq!(
+        f.bank.process(&take),
+        anchor_error(ErrorCode::ConstraintHasOne)
+    );
+}
+
+#[test]
+fn make_rejects_invalid_amount() {
+    let mut f = setup(spl_token::ID);
+    let make = f.make_ix(1, 0, DEPOSIT, 0, None);
+    assert_eq!(
+        f.bank.process(&make),
+        escrow_error(EscrowError::InvalidAmount)
+    );
+}
+
+#[test]
+fn update_offer_rejects_partially_filled_escrow() {
+    let mut f = setup(spl_token::ID);
+    f.make(1);
+    f.bank.process(&f.take_partial_ix(1, RECEIVE / 2)).unwrap();
+
+    let update = f.update_offer_ix(1, Some(RECEIVE * 2));
+    assert_eq!(
+        f.bank.process(&update),
+        escrow_error(EscrowError::InvalidStatus)
+    );
+}
+
+#[test]
+fn update_offer_changes_receive_amount() {
+    let mut f = setup(spl_token::ID);
+    f.make(1);
+    f.bank
+        .process(&f.update_offer_ix(1, Some(RECEIVE * 2)))
+        .unwrap();
+    assert_eq!(f.escrow_state(1).unwrap().receive_amount, RECEIVE * 2);
+}
+
+#[test]
+fn take_partial_rejects_fill_too_large() {
+    let mut f = setup(spl_token::ID);
+    f.make(1);
+    assert_eq!(
+        f.bank.process(&f.take_partial_ix(1, RECEIVE + 1)),
+        escrow_error(EscrowError::FillTooLarge)
+    );
+}
+
+#[test]
+fn take_partial_rejects_fill_too_small() {
+    let mut f = setup(spl_token::ID);
+    f.make(1);
+    // Less than the price of one Token A rounds down to nothing
+    assert_eq!(
+        f.bank.process(&f.take_partial_ix(1, RECEIVE / DEPOSIT - 1)),
+        escrow_error(EscrowError::FillTooSmall)
+    );
+}
+
+#[test]
+fn fill_amount_overflow_is_reported() {
+    let escrow = Escrow {
+        version: ESCROW_VERSION,
+        maker: Pubkey::new_unique(),
+        mint_a: Pubkey::new_unique(),
+        mint_b: Pubkey::new_unique(),
+        receive_amount: RECEIVE,
+        deposit_amount: DEPOSIT,
+        remaining_amount: DEPOSIT,
+        filled_amount: RECEIVE,
+        expires_at: 0,
+        allowed_taker: None,
+        seed: 1,
+        bump: 255,
+        status: EscrowStatus::Deposited,
+        reserved: [0; ESCROW_RESERVED_LEN],
+    };
+    // Nothing left to pay for: the handler maps this to `MathOverflow`
+    assert_eq!(escrow.fill_amount(1), None);
+}
+
+#[test]
+fn expiry_rules() {
+    let mut f = setup(spl_token::ID);
+    let now = f.bank.now();
+
+    let make = f.make_ix(1, RECEIVE, DEPOSIT, now, None);
+    assert_eq!(
+        f.bank.process(&make),
+        escrow_error(EscrowError::InvalidExpiry)
+    );
+
+    let make = f.make_ix(1, RECEIVE, DEPOSIT, now + 60, None);
+    f.bank.process(&make).unwrap();
+    assert_eq!(
+        f.bank.process(&f.expire_ix(1, f.taker)),
+        escrow_error(EscrowError::EscrowNotExpired)
+    );
+
+    f.bank.warp_to(now + 60);
+    assert_eq!(
+        f.bank.process(&f.take_ix(1)),
+        escrow_error(EscrowError::EscrowExpired)
+    );
+
+    // Anyone can return the expired offer to the maker
+    f.bank.process(&f.expire_ix(1, f.taker)).unwrap();
+    assert_eq!(f.bank.token_balance(&f.ata(&f.maker, &f.mint_a)), DEPOSIT);
+    assert!(f.bank.account(&f.escrow(1)).is_none());
+}
+
+#[test]
+fn designated_taker_rejects_others() {
+    let mut f = setup(spl_token::ID);
+    let designated = Pubkey::new_unique();
+    let make = f.make_ix(1, RECEIVE, DEPOSIT, 0, Some(designated));
+    f.bank.process(&make).unwrap();
+
+    assert_eq!(
+        f.bank.process(&f.take_ix(1)),
+        escrow_error(EscrowError::UnauthorizedTaker)
+    );
+}
+
+#[test]
+fn config_rejects_fee_above_cap() {
+    let mut f = setup(spl_token::ID);
+    let update = ix(
+        accounts::UpdateConfigAccounts {
+            admin: f.bank.upgrade_authority,
+            config: f.config(),
+        },
+        instruction::UpdateConfig {
+            fee_bps: MAX_FEE_BPS + 1,
+            treasury: f.treasury,
+        },
+    );
+    assert_eq!(
+        f.bank.process(&update),
+        escrow_error(EscrowError::FeeTooHigh)
+    );
+
+    let config: Config = f.bank.anchor_account(&f.config()).unwrap();
+    assert_eq!(config.fee_bps, FEE_BPS);
+}
+
+#[test]
+fn basket_rejects_bad_legs() {
+    let mut f = setup(spl_token::ID);
+    let leg = |mint: Pubkey, amount: u64| Leg { mint, amount };
+
+    let make = f.make_basket_ix(1, vec![], vec![leg(f.mint_b, RECEIVE)]);
+    assert_eq!(
+        f.bank.process(&make),
+        escrow_error(EscrowError::InvalidLegCount)
+    );
+
+    let make = f.make_basket_ix(
+        1,
+        vec![leg(f.mint_a, 1), leg(f.mint_a, 1)],
+        vec![leg(f.mint_b, RECEIVE)],
+    );
+    assert_eq!(
+        f.bank.process(&make),
+        escrow_error(EscrowError::DuplicateLegMint)
+    );
+
+    let mut make = f.make_basket_ix(1, vec![leg(f.mint_a, 1)], vec![leg(f.mint_b, RECEIVE)]);
+    make.accounts.pop();
+    assert_eq!(
+        f.bank.process(&make),
+        escrow_error(EscrowError::InvalidLegAccount)
+    );
+}
+
+#[test]
+fn basket_rejects_unsupported_requested_mint_at_make() {
+    let mut f = setup(spl_token_2022::ID);
+    let non_transferable =
+        f.bank
+            .create_mint_with_extensions(0, &[ExtensionType::NonTransferable], |mint| {
+                vec![initialize_non_transferable_mint(&spl_token_2022::ID, mint).unwrap()]
+            });
+
+    let make = f.make_basket_ix(
+        1,
+        vec![Leg {
+            mint: f.mint_a,
+            amount: DEPOSIT,
+        }],
+        vec![Leg {
+            mint: non_transferable,
+            amount: 1,
+        }],
+    );
+    assert_eq!(
+        f.bank.process(&make),
+        escrow_error(EscrowError::UnsupportedMintExtension)
+    );
+    assert!(f.bank.account(&f.basket(1)).is_none());
+    assert_eq!(f.bank.token_balance(&f.ata(&f.maker, &f.mint_a)), DEPOSIT);
+}
+
+#[test]
+fn basket_take_settles_every_leg() {
+    let mut f = setup(spl_token::ID);
+    let extra_mint = f.bank.create_mint(&f.token_program, 6);
+    f.bank
+        .create_token_account(&extra_mint, &f.maker, 50, &f.token_program);
+    let offered = vec![
+        Leg {
+            mint: f.mint_a,
+            amount: DEPOSIT,
+        },
+        Leg {
+            mint: extra_mint,
+            amount: 50,
+        },
+    ];
+    let requested = vec![Leg {
+        mint: f.mint_b,
+        amount: RECEIVE,
+    }];
+    f.bank
+        .process(&f.make_basket_ix(1, offered.clone(), requested.clone()))
+        .unwrap();
+    let basket: BasketEscrow = f.bank.anchor_account(&f.basket(1)).unwrap();
+    assert_eq!(basket.offered.len(), 2);
+
+    f.bank
+        .process(&f.take_basket_ix(1, &offered, &requested))
+        .unwrap();
+
+    assert_eq!(f.bank.token_balance(&f.ata(&f.taker, &f.mint_a)), DEPOSIT);
+    assert_eq!(f.bank.token_balance(&f.ata(&f.taker, &extra_mint)), 50);
+    assert!(f.bank.account(&f.basket(1)).is_none());
+}
+
+#[test]
+fn basket_legs_pay_transfer_fees_on_top() {
+    let mut f = setup(spl_token_2022::ID);
+    f.mint_a = transfer_fee_mint(&mut f, 0);
+    f.mint_b = transfer_fee_mint(&mut f, 6);
+    f.bank
+        .create_token_account(&f.mint_a, &f.maker, DEPOSIT + 1, &f.token_program);
+    f.bank
+        .create_token_account(&f.mint_b, &f.taker, 2 * RECEIVE, &f.token_program);
+    let offered = vec![Leg {
+        mint: f.mint_a,
+        amount: DEPOSIT,
+    }];
+    let requested = vec![Leg {
+        mint: f.mint_b,
+        amount: RECEIVE,
+    }];
+
+    // Same rules as a single escrow: the maker covers the fee into the vault...
+    f.bank
+        .process(&f.make_basket_ix(1, offered.clone(), requested.clone()))
+        .unwrap();
+    let vault = f.ata(&f.basket(1), &f.mint_a);
+    assert_eq!(f.bank.token_balance(&vault), DEPOSIT);
+
+    // ...the taker covers it on the requested leg and pays it on the offered one
+    f.bank
+        .process(&f.take_basket_ix(1, &offered, &requested))
+        .unwrap();
+    let protocol_fee = RECEIVE * FEE_BPS as u64 / 10_000;
+    assert_eq!(
+        f.bank.token_balance(&f.ata(&f.maker, &f.mint_b)),
+        RECEIVE - protocol_fee
+    );
+    assert_eq!(
+        f.bank.token_balance(&f.ata(&f.treasury, &f.mint_b)),
+        protocol_fee
+    );
+    assert_eq!(
+        f.bank.token_balance(&f.ata(&f.taker, &f.mint_a)),
+        DEPOSIT - 1
+    );
+    assert_eq!(f.bank.harvested_fees(&f.mint_a), 1);
+    assert!(f.bank.account(&vault).is_none());
+    assert!(f.bank.account(&f.basket(1)).is_none());
+}
+
+#[test]
+fn migrate_upgrades_legacy_escrow() {
+    let mut f = setup(spl_token::ID);
+    let (escrow, bump) = Pubkey::find_program_address(
+        &[ESCROW_SEED, &7u64.to_le_bytes(), f.maker.as_ref()],
+        &PROGRAM_ID,
+    );
+    let mut data = Escrow::DISCRIMINATOR.to_vec();
+    EscrowV0 {
+        maker: f.maker,
+        mint_a: f.mint_a,
+        mint_b: f.mint_b,
+        receive_amount: RECEIVE,
+        seed: 7,
+        bump,
+        status: EscrowStatus::Deposited,
+    }
+    .serialize(&mut data)
+    .unwrap();
+    assert_eq!(data.len(), Escrow::DISCRIMINATOR.len() + EscrowV0::LEN);
+    f.bank.set_account(escrow, data, PROGRAM_ID);
+    f.bank
+        .create_token_account(&f.mint_a, &escrow, DEPOSIT, &f.token_program);
+
+    let migrate = ix(
+        accounts::MigrateEscrowAccounts {
+            payer: f.taker,
+            escrow,
+            mint_a: f.mint_a,
+            vault: f.vault(7),
+            token_program: f.token_program,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateEscrow {},
+    );
+    f.bank.process(&migrate).unwrap();
+
+    let migrated = f.escrow_state(7).unwrap();
+    assert_eq!(migrated.version, ESCROW_VERSION);
+    assert_eq!(migrated.remaining_amount, DEPOSIT);
+    assert_eq!(
+        f.bank.lamports(&escrow),
+        Rent::default().minimum_balance(Escrow::DISCRIMINATOR.len() + Escrow::INIT_SPACE)
+    );
+
+    // The migrated escrow trades like any other
+    assert_eq!(
+        f.bank.process(&migrate),
+        escrow_error(EscrowError::AlreadyMigrated)
+    );
+    f.bank.process(&f.take_ix(7)).unwrap();
+    assert_eq!(f.bank.token_balance(&f.ata(&f.taker, &f.mint_a)), DEPOSIT);
+}
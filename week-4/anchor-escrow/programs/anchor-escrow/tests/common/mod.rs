@@ -0,0 +1,376 @@
+//! In-process bank for running the escrow program under `cargo test`.
+//!
+//! The escrow and the test transfer hook run natively through
+//! `solana-program-test`, so no SBF build is needed. Token, Token-2022 and
+//! Associated Token are the real SPL programs bundled with it, and every mint
+//! and token account is created through them rather than written in place.
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+use anchor_escrow::ID as PROGRAM_ID;
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        bpf_loader_upgradeable, entrypoint::ProgramResult, instruction::Instruction,
+        program_pack::Pack,
+    },
+    InstructionData,
+};
+use anchor_spl::{
+    associated_token::{
+        get_associated_token_address_with_program_id,
+        spl_associated_token_account::instruction::create_associated_token_account_idempotent,
+    },
+    token_2022::spl_token_2022::{
+        self,
+        extension::{
+            transfer_fee::TransferFeeConfig, BaseStateWithExtensions, ExtensionType,
+            StateWithExtensions,
+        },
+        state::{Account as TokenAccount, Mint},
+    },
+};
+use solana_account::{Account, AccountSharedData};
+use solana_keypair::Keypair;
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_transaction_error::TransactionError;
+use tokio::runtime::Runtime;
+
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+pub const START_TIME: i64 = 1_700_000_000;
+
+pub struct Bank {
+    runtime: Runtime,
+    context: ProgramTestContext,
+    // Keypairs for every wallet the bank handed out, used to sign for them
+    wallets: HashMap<Pubkey, Keypair>,
+    mint_authority: Keypair,
+    pub upgrade_authority: Pubkey,
+    // Kept here because moving to a new slot resets the clock
+    unix_timestamp: i64,
+}
+
+impl Bank {
+    pub fn new() -> Self {
+        let mut program_test = ProgramTest::default();
+        program_test.prefer_bpf(false);
+        program_test.add_program("anchor_escrow", PROGRAM_ID, processor!(process_escrow));
+        program_test.add_program(
+            "transfer_hook_counter",
+            transfer_hook_counter::ID,
+            processor!(process_hook),
+        );
+        program_test.set_compute_max_units(1_400_000);
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let context = runtime.block_on(program_test.start_with_context());
+        let mut bank = Bank {
+            runtime,
+            context,
+            wallets: HashMap::new(),
+            mint_authority: Keypair::new(),
+            upgrade_authority: Pubkey::default(),
+            unix_timestamp: START_TIME,
+        };
+        bank.upgrade_authority = bank.wallet(LAMPORTS_PER_SOL);
+        let payer = bank.mint_authority.pubkey();
+        bank.airdrop(&payer, 10 * LAMPORTS_PER_SOL);
+        bank.warp_to(START_TIME);
+
+        // ProgramData header only: the program runs natively, so there is no ELF
+        let mut data = 3u32.to_le_bytes().to_vec();
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.push(1);
+        data.extend_from_slice(bank.upgrade_authority.as_ref());
+        bank.set_account(program_data_address(), data, bpf_loader_upgradeable::ID);
+        bank
+    }
+
+    pub fn now(&self) -> i64 {
+        self.clock().unix_timestamp
+    }
+
+    fn clock(&self) -> Clock {
+        self.runtime
+            .block_on(self.context.banks_client.clone().get_sysvar::<Clock>())
+            .unwrap()
+    }
+
+    pub fn warp_to(&mut self, unix_timestamp: i64) {
+        self.unix_timestamp = unix_timestamp;
+        let mut clock = self.clock();
+        clock.unix_timestamp = unix_timestamp;
+        self.context.set_sysvar(&clock);
+    }
+
+    fn minimum_balance(&self, len: usize) -> u64 {
+        self.runtime
+            .block_on(self.context.banks_client.clone().get_rent())
+            .unwrap()
+            .minimum_balance(len)
+    }
+
+    // Rent-exempt account with `data`, written in place
+    pub fn set_account(&mut self, key: Pubkey, data: Vec<u8>, owner: Pubkey) {
+        let lamports = self.minimum_balance(data.len());
+        self.context.set_account(
+            &key,
+            &AccountSharedData::from(Account {
+                lamports,
+                data,
+                owner,
+                executable: false,
+                rent_epoch: 0,
+            }),
+        );
+    }
+
+    pub fn account(&self, key: &Pubkey) -> Option<Account> {
+        self.runtime
+            .block_on(self.context.banks_client.clone().get_account(*key))
+            .unwrap()
+            .filter(|account| account.lamports > 0)
+    }
+
+    pub fn lamports(&self, key: &Pubkey) -> u64 {
+        self.account(key).map_or(0, |account| account.lamports)
+    }
+
+    pub fn airdrop(&mut self, key: &Pubkey, lamports: u64) {
+        let mut account = self.account(key).unwrap_or_else(|| Account {
+            lamports: 0,
+            data: Vec::new(),
+            owner: anchor_lang::system_program::ID,
+            executable: false,
+            rent_epoch: 0,
+        });
+        account.lamports += lamports;
+        self.context
+            .set_account(key, &AccountSharedData::from(account));
+    }
+
+    // Funded system account whose keypair the bank signs with
+    pub fn wallet(&mut self, lamports: u64) -> Pubkey {
+        let keypair = Keypair::new();
+        let key = keypair.pubkey();
+        self.wallets.insert(key, keypair);
+        self.airdrop(&key, lamports);
+        key
+    }
+
+    pub fn create_mint(&mut self, token_program: &Pubkey, decimals: u8) -> Pubkey {
+        self.create_mint_with(token_program, decimals, &[], |_| Vec::new())
+    }
+
+    // Token-2022 mint whose extensions are initialised by the instructions from `configure`
+    pub fn create_mint_with_extensions(
+        &mut self,
+        decimals: u8,
+        extensions: &[ExtensionType],
+        configure: impl FnOnce(&Pubkey) -> Vec<Instruction>,
+    ) -> Pubkey {
+        self.create_mint_with(&spl_token_2022::ID, decimals, extensions, configure)
+    }
+
+    fn create_mint_with(
+        &mut self,
+        token_program: &Pubkey,
+        decimals: u8,
+        extensions: &[ExtensionType],
+        configure: impl FnOnce(&Pubkey) -> Vec<Instruction>,
+    ) -> Pubkey {
+        let mint = Keypair::new();
+        let payer = self.mint_authority.pubkey();
+        let space = if extensions.is_empty() {
+            Mint::LEN
+        } else {
+            ExtensionType::try_calculate_account_len::<Mint>(extensions).unwrap()
+        };
+        let mut ixs = vec![
+            anchor_lang::solana_program::system_instruction::create_account(
+                &payer,
+                &mint.pubkey(),
+                self.minimum_balance(space),
+                space as u64,
+                token_program,
+            ),
+        ];
+        ixs.extend(configure(&mint.pubkey()));
+        ixs.push(
+            spl_token_2022::instruction::initialize_mint2(
+                token_program,
+                &mint.pubkey(),
+                &payer,
+                None,
+                decimals,
+            )
+            .unwrap(),
+        );
+        self.send(&ixs, &[&self.mint_authority.insecure_clone(), &mint])
+            .unwrap();
+        mint.pubkey()
+    }
+
+    // Creates `owner`'s associated token account for `mint` if needed and mints `amount` into it
+    pub fn create_token_account(
+        &mut self,
+        mint: &Pubkey,
+        owner: &Pubkey,
+        amount: u64,
+        token_program: &Pubkey,
+    ) -> Pubkey {
+        let payer = self.mint_authority.pubkey();
+        let ata = get_associated_token_address_with_program_id(owner, mint, token_program);
+        let mut ixs = vec![create_associated_token_account_idempotent(
+            &payer,
+            owner,
+            mint,
+            token_program,
+        )];
+        if amount > 0 {
+            let decimals = self.mint_decimals(mint);
+            ixs.push(
+                spl_token_2022::instruction::mint_to_checked(
+                    token_program,
+                    mint,
+                    &ata,
+                    &payer,
+                    &[],
+                    amount,
+                    decimals,
+                )
+                .unwrap(),
+            );
+        }
+        self.send(&ixs, &[&self.mint_authority.insecure_clone()])
+            .unwrap();
+        ata
+    }
+
+    fn mint_decimals(&self, mint: &Pubkey) -> u8 {
+        let account = self.account(mint).unwrap();
+        StateWithExtensions::<Mint>::unpack(&account.data)
+            .unwrap()
+            .base
+            .decimals
+    }
+
+    pub fn token_balance(&self, key: &Pubkey) -> u64 {
+        self.account(key).map_or(0, |account| {
+            StateWithExtensions::<TokenAccount>::unpack(&account.data)
+                .unwrap()
+                .base
+                .amount
+        })
+    }
+
+    // Transfer fees harvested into a Token-2022 mint, waiting to be withdrawn
+    pub fn harvested_fees(&self, mint: &Pubkey) -> u64 {
+        let account = self.account(mint).unwrap();
+        let state = StateWithExtensions::<Mint>::unpack(&account.data).unwrap();
+        state
+            .get_extension::<TransferFeeConfig>()
+            .map_or(0, |config| u64::from(config.withheld_amount))
+    }
+
+    pub fn anchor_account<T: AccountDeserialize>(&self, key: &Pubkey) -> Option<T> {
+        self.account(key)
+            .map(|account| T::try_deserialize(&mut &account.data[..]).unwrap())
+    }
+
+    // Runs one instruction in its own transaction, signed by the bank's wallets;
+    // account changes are only kept if it succeeds
+    pub fn process(&mut self, ix: &Instruction) -> std::result::Result<(), TransactionError> {
+        let signers: Vec<Keypair> = ix
+            .accounts
+            .iter()
+            .filter(|meta| meta.is_signer)
+            .map(|meta| {
+                self.wallets
+                    .get(&meta.pubkey)
+                    .unwrap_or_else(|| panic!("no keypair for signer {}", meta.pubkey))
+                    .insecure_clone()
+            })
+            .collect();
+        let signers: Vec<&Keypair> = signers.iter().collect();
+        self.send(std::slice::from_ref(ix), &signers)
+    }
+
+    fn send(
+        &mut self,
+        ixs: &[Instruction],
+        signers: &[&Keypair],
+    ) -> std::result::Result<(), TransactionError> {
+        // A new slot and blockhash each time, so retrying an instruction isn't a
+        // duplicate; the clock is put back to the test's time afterwards
+        let slot = self.clock().slot;
+        self.context.warp_to_slot(slot + 1).unwrap();
+        self.warp_to(self.unix_timestamp);
+
+        let banks_client = self.context.banks_client.clone();
+        self.runtime.block_on(async {
+            let blockhash = banks_client.get_latest_blockhash().await.unwrap();
+            let tx = Transaction::new_signed_with_payer(
+                ixs,
+                Some(&signers[0].pubkey()),
+                signers,
+                blockhash,
+            );
+            banks_client
+                .process_transaction(tx)
+                .await
+                .map_err(|err| err.unwrap())
+        })
+    }
+}
+
+pub fn program_data_address() -> Pubkey {
+    Pubkey::find_program_address(&[PROGRAM_ID.as_ref()], &bpf_loader_upgradeable::ID).0
+}
+
+pub fn instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+// `processor!` takes a plain entrypoint, while Anchor's `entry` ties the account
+// slice to the accounts' own lifetime; a leaked copy of the slice bridges the two
+fn process_escrow(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(accounts.iter().map(as_deployed).collect::<Box<[_]>>());
+    anchor_escrow::entry(program_id, accounts, data)
+}
+
+fn process_hook(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(accounts.to_vec().into_boxed_slice());
+    transfer_hook_counter::entry(program_id, accounts, data)
+}
+
+// A natively run program is owned by the native loader. `initialize_config` reads
+// the upgrade authority through the upgradeable loader's program account, so the
+// escrow sees its own account as that loader would present it.
+fn as_deployed<'a>(account: &AccountInfo<'a>) -> AccountInfo<'a> {
+    if *account.key != PROGRAM_ID {
+        return account.clone();
+    }
+    let mut data = 2u32.to_le_bytes().to_vec();
+    data.extend_from_slice(program_data_address().as_ref());
+    AccountInfo::new(
+        account.key,
+        false,
+        false,
+        Box::leak(Box::new(account.lamports())),
+        Box::leak(data.into_boxed_slice()),
+        &bpf_loader_upgradeable::ID,
+        true,
+        0,
+    )
+}
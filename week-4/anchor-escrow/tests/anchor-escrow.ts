@@ -36,8 +36,32 @@ describe("anchor-escrow", () => {
   let escrowBump: number;
   let vault: anchor.web3.PublicKey;
 
+  const treasury = anchor.web3.Keypair.generate();
+  let treasuryAtaPayment: anchor.web3.PublicKey;
+  const [configPda] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("config")],
+    PROGRAM_ID
+  );
+  const [programData] = anchor.web3.PublicKey.findProgramAddressSync(
+    [PROGRAM_ID.toBuffer()],
+    new anchor.web3.PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+  );
+
   const receiveAmount = 100;
   const depositAmount = 1;
+  const feeBps = 100; // 1%
+
+  const eventParser = new anchor.EventParser(
+    PROGRAM_ID,
+    new anchor.BorshCoder(program.idl)
+  );
+  const parseEvents = async (txSignature: string) => {
+    const txDetails = await provider.connection.getTransaction(txSignature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    return [...eventParser.parseLogs(txDetails.meta.logMessages)];
+  };
 
   const logTransactionResult = (label: string, txSignature: string) => {
     console.log(`\n${label}:`);
@@ -120,6 +144,22 @@ describe("anchor-escrow", () => {
 
     takerAtaNft = getAssociatedTokenAddressSync(nftMint, taker.publicKey);
     makerAtaPayment = getAssociatedTokenAddressSync(paymentMint, maker);
+    treasuryAtaPayment = getAssociatedTokenAddressSync(
+      paymentMint,
+      treasury.publicKey
+    );
+
+    // Protocol fee config, created by the program upgrade authority
+    await program.methods
+      .initializeConfig(feeBps, treasury.publicKey)
+      .accounts({
+        admin: maker,
+        config: configPda,
+        program: PROGRAM_ID,
+        programData,
+        systemProgram: SYSTEM_PROGRAM,
+      })
+      .rpc({ commitment: "confirmed" });
 
     // Compute the escrow PDA
     [escrowPda, escrowBump] = anchor.web3.PublicKey.findProgramAddressSync(
@@ -132,7 +172,13 @@ describe("anchor-escrow", () => {
 
   it("Initializing Escrow, Vault and Deposit", async () => {
     const tx = await program.methods
-      .make(seed, new BN(receiveAmount * 1e6), new BN(depositAmount))
+      .make(
+        seed,
+        new BN(receiveAmount * 1e6),
+        new BN(depositAmount),
+        new BN(0),
+        null
+      )
       .accounts({
         maker,
         escrow: escrowPda,
@@ -156,6 +202,12 @@ describe("anchor-escrow", () => {
       receiveAmount * 1e6
     );
     expect(escrowAccount.bump).to.equal(escrowBump);
+    expect(escrowAccount.version).to.equal(1);
+
+    const [made] = await parseEvents(tx);
+    expect(made.name).to.equal("escrowMade");
+    expect(made.data.maker.toBase58()).to.equal(maker.toBase58());
+    expect(made.data.depositAmount.toNumber()).to.equal(depositAmount);
 
     const vaultBalance = (
       await provider.connection.getTokenAccountBalance(vault)
@@ -176,6 +228,9 @@ describe("anchor-escrow", () => {
         takerAtaA: takerAtaNft,
         takerAtaB: takerAtaPayment,
         makerAtaB: makerAtaPayment,
+        config: configPda,
+        treasury: treasury.publicKey,
+        treasuryAtaB: treasuryAtaPayment,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SYSTEM_PROGRAM,
@@ -184,6 +239,11 @@ describe("anchor-escrow", () => {
       .rpc({ commitment: "confirmed" });
     logTransactionResult("Take escrow Completed", tx);
 
+    const [taken] = await parseEvents(tx);
+    expect(taken.name).to.equal("escrowTaken");
+    expect(taken.data.taker.toBase58()).to.equal(taker.publicKey.toBase58());
+    expect(taken.data.remainingAmount.toNumber()).to.equal(0);
+
     const escrowInfo = await provider.connection.getAccountInfo(escrowPda);
     expect(escrowInfo).to.be.null;
 
@@ -200,7 +260,100 @@ describe("anchor-escrow", () => {
     const makerPaymentAccount = (
       await provider.connection.getTokenAccountBalance(makerAtaPayment)
     ).value.uiAmount;
-    expect(makerPaymentAccount).to.equal(receiveAmount);
+    const fee = (receiveAmount * feeBps) / 10_000;
+    expect(makerPaymentAccount).to.equal(receiveAmount - fee);
+
+    //treasury fee account
+    const treasuryPaymentAccount = (
+      await provider.connection.getTokenAccountBalance(treasuryAtaPayment)
+    ).value.uiAmount;
+    expect(treasuryPaymentAccount).to.equal(fee);
+  });
+
+  it("Taker fills an escrow in two partial takes", async () => {
+    const seed = new BN(Math.floor(Math.random() * 1000));
+    [escrowPda, escrowBump] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow"), seed.toBuffer("le", 8), maker.toBuffer()],
+      PROGRAM_ID
+    );
+    vault = getAssociatedTokenAddressSync(nftMint, escrowPda, true);
+    await mintTo(
+      provider.connection,
+      provider.wallet.payer,
+      nftMint,
+      makerAtaNft,
+      provider.wallet.payer,
+      10
+    );
+    await program.methods
+      .make(
+        seed,
+        new BN(receiveAmount * 1e6),
+        new BN(10),
+        new BN(0),
+        null
+      )
+      .accounts({
+        maker,
+        escrow: escrowPda,
+        mintA: nftMint,
+        mintB: paymentMint,
+        vault,
+        makerAtaA: makerAtaNft,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SYSTEM_PROGRAM,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    const takeAccounts = {
+      taker: taker.publicKey,
+      maker,
+      escrow: escrowPda,
+      mintA: nftMint,
+      mintB: paymentMint,
+      vault,
+      takerAtaA: takerAtaNft,
+      takerAtaB: takerAtaPayment,
+      makerAtaB: makerAtaPayment,
+      config: configPda,
+      treasury: treasury.publicKey,
+      treasuryAtaB: treasuryAtaPayment,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SYSTEM_PROGRAM,
+    };
+    const takerNftBefore = (
+      await provider.connection.getTokenAccountBalance(takerAtaNft)
+    ).value.uiAmount;
+
+    // 45 of 100 payment tokens buys floor(4.5) = 4 of the 10 deposited
+    const tx = await program.methods
+      .takePartial(new BN(45 * 1e6))
+      .accounts(takeAccounts)
+      .signers([taker])
+      .rpc({ commitment: "confirmed" });
+    logTransactionResult("Partial take", tx);
+
+    const escrowAccount = await program.account.escrow.fetch(escrowPda);
+    expect(escrowAccount.status).to.have.property("partiallyFilled");
+    expect(escrowAccount.remainingAmount.toNumber()).to.equal(6);
+    expect(escrowAccount.filledAmount.toNumber()).to.equal(45 * 1e6);
+
+    // The rest of the escrow is taken in full
+    await program.methods
+      .take()
+      .accounts(takeAccounts)
+      .signers([taker])
+      .rpc({ commitment: "confirmed" });
+
+    const escrowInfo = await provider.connection.getAccountInfo(escrowPda);
+    expect(escrowInfo).to.be.null;
+
+    const takerNftAfter = (
+      await provider.connection.getTokenAccountBalance(takerAtaNft)
+    ).value.uiAmount;
+    expect(takerNftAfter - takerNftBefore).to.equal(10);
   });
 
   it("Initializing Escrow, Vault, Deposit and Refund", async () => {
@@ -219,7 +372,13 @@ describe("anchor-escrow", () => {
       1
     );
     await program.methods
-      .make(seed, new BN(receiveAmount * 1e6), new BN(depositAmount))
+      .make(
+        seed,
+        new BN(receiveAmount * 1e6),
+        new BN(depositAmount),
+        new BN(0),
+        null
+      )
       .accounts({
         maker,
         escrow: escrowPda,
@@ -248,10 +407,416 @@ describe("anchor-escrow", () => {
       .rpc({ commitment: "confirmed" });
     logTransactionResult("Refund", tx_refund);
 
+    const [refunded] = await parseEvents(tx_refund);
+    expect(refunded.name).to.equal("escrowRefunded");
+    expect(refunded.data.amount.toNumber()).to.equal(depositAmount);
+
     const escrowInfo = await provider.connection.getAccountInfo(escrowPda);
     expect(escrowInfo).to.be.null;
 
     const vaultBalance = await provider.connection.getAccountInfo(vault);
     expect(vaultBalance).to.be.null;
   });
+
+  it("Expired escrow is returned to the maker by anyone", async () => {
+    const seed = new BN(Math.floor(Math.random() * 1000));
+    [escrowPda, escrowBump] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow"), seed.toBuffer("le", 8), maker.toBuffer()],
+      PROGRAM_ID
+    );
+    vault = getAssociatedTokenAddressSync(nftMint, escrowPda, true);
+    await mintTo(
+      provider.connection,
+      provider.wallet.payer,
+      nftMint,
+      makerAtaNft,
+      provider.wallet.payer,
+      1
+    );
+    const expiresAt = Math.floor(Date.now() / 1000) + 2;
+    await program.methods
+      .make(
+        seed,
+        new BN(receiveAmount * 1e6),
+        new BN(depositAmount),
+        new BN(expiresAt),
+        null
+      )
+      .accounts({
+        maker,
+        escrow: escrowPda,
+        mintA: nftMint,
+        mintB: paymentMint,
+        vault,
+        makerAtaA: makerAtaNft,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SYSTEM_PROGRAM,
+      })
+      .rpc({ commitment: "confirmed" });
+    await new Promise((resolve) => setTimeout(resolve, 4000));
+
+    try {
+      await program.methods
+        .take()
+        .accounts({
+          taker: taker.publicKey,
+          maker,
+          escrow: escrowPda,
+          mintA: nftMint,
+          mintB: paymentMint,
+          vault,
+          takerAtaA: takerAtaNft,
+          takerAtaB: takerAtaPayment,
+          makerAtaB: makerAtaPayment,
+          config: configPda,
+          treasury: treasury.publicKey,
+          treasuryAtaB: treasuryAtaPayment,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SYSTEM_PROGRAM,
+        })
+        .signers([taker])
+        .rpc({ commitment: "confirmed" });
+      expect.fail("Take of an expired escrow should have failed.");
+    } catch (err) {
+      expect(err.toString()).to.include("EscrowExpired");
+    }
+
+    // The taker, not the maker, cleans up the expired offer
+    const tx = await program.methods
+      .expire()
+      .accounts({
+        caller: taker.publicKey,
+        maker,
+        escrow: escrowPda,
+        mintA: nftMint,
+        vault,
+        makerAtaA: makerAtaNft,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SYSTEM_PROGRAM,
+      })
+      .signers([taker])
+      .rpc({ commitment: "confirmed" });
+    logTransactionResult("Expire", tx);
+
+    const escrowInfo = await provider.connection.getAccountInfo(escrowPda);
+    expect(escrowInfo).to.be.null;
+
+    const vaultInfo = await provider.connection.getAccountInfo(vault);
+    expect(vaultInfo).to.be.null;
+  });
+
+  it("Designated-taker escrow rejects any other taker", async () => {
+    const seed = new BN(Math.floor(Math.random() * 1000));
+    const otherTaker = anchor.web3.Keypair.generate();
+    [escrowPda, escrowBump] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow"), seed.toBuffer("le", 8), maker.toBuffer()],
+      PROGRAM_ID
+    );
+    vault = getAssociatedTokenAddressSync(nftMint, escrowPda, true);
+    await mintTo(
+      provider.connection,
+      provider.wallet.payer,
+      nftMint,
+      makerAtaNft,
+      provider.wallet.payer,
+      1
+    );
+    await program.methods
+      .make(
+        seed,
+        new BN(receiveAmount * 1e6),
+        new BN(depositAmount),
+        new BN(0),
+        otherTaker.publicKey
+      )
+      .accounts({
+        maker,
+        escrow: escrowPda,
+        mintA: nftMint,
+        mintB: paymentMint,
+        vault,
+        makerAtaA: makerAtaNft,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SYSTEM_PROGRAM,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    const escrowAccount = await program.account.escrow.fetch(escrowPda);
+    expect(escrowAccount.allowedTaker.toBase58()).to.equal(
+      otherTaker.publicKey.toBase58()
+    );
+
+    try {
+      await program.methods
+        .take()
+        .accounts({
+          taker: taker.publicKey,
+          maker,
+          escrow: escrowPda,
+          mintA: nftMint,
+          mintB: paymentMint,
+          vault,
+          takerAtaA: takerAtaNft,
+          takerAtaB: takerAtaPayment,
+          makerAtaB: makerAtaPayment,
+          config: configPda,
+          treasury: treasury.publicKey,
+          treasuryAtaB: treasuryAtaPayment,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SYSTEM_PROGRAM,
+        })
+        .signers([taker])
+        .rpc({ commitment: "confirmed" });
+      expect.fail("Take by a non-designated taker should have failed.");
+    } catch (err) {
+      expect(err.toString()).to.include("UnauthorizedTaker");
+    }
+
+    await program.methods
+      .refund()
+      .accounts({
+        maker,
+        escrow: escrowPda,
+        mintA: nftMint,
+        vault,
+        makerAtaA: makerAtaNft,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SYSTEM_PROGRAM,
+      })
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("Update config above the fee cap! Should Fail", async () => {
+    try {
+      await program.methods
+        .updateConfig(10_001, treasury.publicKey)
+        .accounts({
+          admin: maker,
+          config: configPda,
+        })
+        .rpc({ commitment: "confirmed" });
+      expect.fail("Fee above the cap should have failed.");
+    } catch (err) {
+      expect(err.toString()).to.include("FeeTooHigh");
+    }
+
+    const configAccount = await program.account.config.fetch(configPda);
+    expect(configAccount.feeBps).to.equal(feeBps);
+  });
+
+  it("Basket escrow swaps two tokens for one in a single take", async () => {
+    const seed = new BN(Math.floor(Math.random() * 1000));
+    const [basketPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("basket"), seed.toBuffer("le", 8), maker.toBuffer()],
+      PROGRAM_ID
+    );
+
+    // Second offered token, minted to the maker
+    const extraMint = await createMint(
+      provider.connection,
+      provider.wallet.payer,
+      maker,
+      null,
+      6
+    );
+    const makerAtaExtra = getAssociatedTokenAddressSync(extraMint, maker);
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountInstruction(
+          maker,
+          makerAtaExtra,
+          maker,
+          extraMint
+        )
+      )
+    );
+    await mintTo(
+      provider.connection,
+      provider.wallet.payer,
+      extraMint,
+      makerAtaExtra,
+      provider.wallet.payer,
+      50 * 1e6
+    );
+    await mintTo(
+      provider.connection,
+      provider.wallet.payer,
+      nftMint,
+      makerAtaNft,
+      provider.wallet.payer,
+      1
+    );
+
+    const offered = [
+      { mint: nftMint, amount: new BN(1) },
+      { mint: extraMint, amount: new BN(50 * 1e6) },
+    ];
+    const requested = [{ mint: paymentMint, amount: new BN(10 * 1e6) }];
+    const basketVault = (mint: anchor.web3.PublicKey) =>
+      getAssociatedTokenAddressSync(mint, basketPda, true);
+    const meta = (pubkey: anchor.web3.PublicKey, isWritable: boolean) => ({
+      pubkey,
+      isWritable,
+      isSigner: false,
+    });
+
+    await program.methods
+      .makeBasket(seed, offered, requested)
+      .accounts({
+        maker,
+        basket: basketPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SYSTEM_PROGRAM,
+      })
+      .remainingAccounts([
+        meta(nftMint, false),
+        meta(makerAtaNft, true),
+        meta(basketVault(nftMint), true),
+        meta(extraMint, false),
+        meta(makerAtaExtra, true),
+        meta(basketVault(extraMint), true),
+        meta(paymentMint, false),
+      ])
+      .rpc({ commitment: "confirmed" });
+
+    const basketAccount = await program.account.basketEscrow.fetch(basketPda);
+    expect(basketAccount.offered.length).to.equal(2);
+    expect(basketAccount.requested.length).to.equal(1);
+
+    const tx = await program.methods
+      .takeBasket()
+      .accounts({
+        taker: taker.publicKey,
+        maker,
+        basket: basketPda,
+        config: configPda,
+        treasury: treasury.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SYSTEM_PROGRAM,
+      })
+      .remainingAccounts([
+        meta(nftMint, false),
+        meta(basketVault(nftMint), true),
+        meta(takerAtaNft, true),
+        meta(extraMint, false),
+        meta(basketVault(extraMint), true),
+        meta(getAssociatedTokenAddressSync(extraMint, taker.publicKey), true),
+        meta(paymentMint, false),
+        meta(takerAtaPayment, true),
+        meta(makerAtaPayment, true),
+        meta(treasuryAtaPayment, true),
+      ])
+      .signers([taker])
+      .rpc({ commitment: "confirmed" });
+    logTransactionResult("Take basket", tx);
+
+    const [taken] = await parseEvents(tx);
+    expect(taken.name).to.equal("basketTaken");
+    expect(taken.data.taker.toBase58()).to.equal(taker.publicKey.toBase58());
+    expect(taken.data.offered.length).to.equal(2);
+    expect(taken.data.requested[0].mint.toBase58()).to.equal(
+      paymentMint.toBase58()
+    );
+
+    const basketInfo = await provider.connection.getAccountInfo(basketPda);
+    expect(basketInfo).to.be.null;
+    expect(
+      await provider.connection.getAccountInfo(basketVault(nftMint))
+    ).to.be.null;
+    expect(
+      await provider.connection.getAccountInfo(basketVault(extraMint))
+    ).to.be.null;
+
+    const takerExtra = (
+      await provider.connection.getTokenAccountBalance(
+        getAssociatedTokenAddressSync(extraMint, taker.publicKey)
+      )
+    ).value.uiAmount;
+    expect(takerExtra).to.equal(50);
+  });
+
+  it("Maker updates an open offer before anyone takes it", async () => {
+    const seed = new BN(Math.floor(Math.random() * 1000));
+    [escrowPda, escrowBump] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow"), seed.toBuffer("le", 8), maker.toBuffer()],
+      PROGRAM_ID
+    );
+    vault = getAssociatedTokenAddressSync(nftMint, escrowPda, true);
+    await mintTo(
+      provider.connection,
+      provider.wallet.payer,
+      nftMint,
+      makerAtaNft,
+      provider.wallet.payer,
+      5
+    );
+    await program.methods
+      .make(
+        seed,
+        new BN(receiveAmount * 1e6),
+        new BN(2),
+        new BN(0),
+        null
+      )
+      .accounts({
+        maker,
+        escrow: escrowPda,
+        mintA: nftMint,
+        mintB: paymentMint,
+        vault,
+        makerAtaA: makerAtaNft,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SYSTEM_PROGRAM,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    // Ask for half as much and top the vault up from 2 to 5
+    const tx = await program.methods
+      .updateOffer(new BN((receiveAmount / 2) * 1e6), new BN(5))
+      .accounts({
+        maker,
+        escrow: escrowPda,
+        mintA: nftMint,
+        newMintB: null,
+        vault,
+        makerAtaA: makerAtaNft,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+    logTransactionResult("Update offer", tx);
+
+    const escrowAccount = await program.account.escrow.fetch(escrowPda);
+    expect(escrowAccount.receiveAmount.toNumber()).to.equal(
+      (receiveAmount / 2) * 1e6
+    );
+    expect(escrowAccount.depositAmount.toNumber()).to.equal(5);
+
+    const vaultBalance = (
+      await provider.connection.getTokenAccountBalance(vault)
+    ).value.uiAmount;
+    expect(vaultBalance).to.equal(5);
+
+    await program.methods
+      .refund()
+      .accounts({
+        maker,
+        escrow: escrowPda,
+        mintA: nftMint,
+        vault,
+        makerAtaA: makerAtaNft,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SYSTEM_PROGRAM,
+      })
+      .rpc({ commitment: "confirmed" });
+  });
 });
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use spl_discriminator::SplDiscriminate;
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+
+declare_id!("BgSPP9ek1LntAq2G6qKP8YwzaLucebC7uP6pMPcr8emp");
+
+// Test-only transfer hook: counts every transfer Token-2022 routes through it,
+// so the escrow tests can check the hook really ran
+#[program]
+pub mod transfer_hook_counter {
+    use super::*;
+
+    #[instruction(discriminator = ExecuteInstruction::SPL_DISCRIMINATOR_SLICE)]
+    pub fn execute(ctx: Context<ExecuteAccounts>, _amount: u64) -> Result<()> {
+        ctx.accounts.counter.transfers += 1;
+        Ok(())
+    }
+}
+
+// Account order is fixed by the transfer hook interface
+#[derive(Accounts)]
+pub struct ExecuteAccounts<'info> {
+    /// CHECK: source token account, validated by Token-2022
+    pub source: UncheckedAccount<'info>,
+    /// CHECK: mint, validated by Token-2022
+    pub mint: UncheckedAccount<'info>,
+    /// CHECK: destination token account, validated by Token-2022
+    pub destination: UncheckedAccount<'info>,
+    /// CHECK: source owner or delegate, validated by Token-2022
+    pub authority: UncheckedAccount<'info>,
+    /// CHECK: extra account metas, resolved by Token-2022
+    pub extra_account_metas: UncheckedAccount<'info>,
+
+    // The single extra account listed in `extra_account_metas`
+    #[account(mut)]
+    pub counter: Account<'info, Counter>,
+}
+
+#[account]
+pub struct Counter {
+    pub transfers: u64,
+}
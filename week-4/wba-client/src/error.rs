@@ -0,0 +1,113 @@
+use anchor_escrow::error::EscrowError;
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use simple_vault::error::VaultError;
+use solana_instruction::error::InstructionError;
+
+// In declaration order, so a variant's index is its code less `ERROR_CODE_OFFSET`
+const VAULT_ERRORS: &[VaultError] = &[
+    VaultError::Unauthorized,
+    VaultError::InsufficientFunds,
+    VaultError::InvalidAmount,
+    VaultError::VaultLocked,
+    VaultError::InvalidUnlockTime,
+    VaultError::AllowanceExceeded,
+    VaultError::MultisigRequired,
+    VaultError::InvalidMultisig,
+    VaultError::MultisigAlreadyEnabled,
+    VaultError::AlreadyApproved,
+    VaultError::ThresholdNotMet,
+    VaultError::ProposalStale,
+    VaultError::ProposalNotStale,
+    VaultError::WithdrawCapExceeded,
+    VaultError::VaultNotEmpty,
+    VaultError::InvalidGuardians,
+    VaultError::RecoveryTimelocked,
+    VaultError::StakeActive,
+    VaultError::InvalidSchedule,
+    VaultError::NothingAccrued,
+    VaultError::InvalidVesting,
+    VaultError::NothingVested,
+    VaultError::NotRevocable,
+    VaultError::ProposalAssetMismatch,
+    VaultError::TransferFeeMint,
+    VaultError::TransferHookMint,
+    VaultError::StaleAllowance,
+    VaultError::RecoveryStale,
+    VaultError::RecoveryNotStale,
+];
+
+const ESCROW_ERRORS: &[EscrowError] = &[
+    EscrowError::InvalidAmount,
+    EscrowError::InvalidStatus,
+    EscrowError::FillTooLarge,
+    EscrowError::FillTooSmall,
+    EscrowError::MathOverflow,
+    EscrowError::InvalidExpiry,
+    EscrowError::EscrowExpired,
+    EscrowError::EscrowNotExpired,
+    EscrowError::UnauthorizedTaker,
+    EscrowError::FeeTooHigh,
+    EscrowError::InvalidLegCount,
+    EscrowError::DuplicateLegMint,
+    EscrowError::InvalidLegAccount,
+    EscrowError::AlreadyMigrated,
+    EscrowError::UnsupportedMintExtension,
+];
+
+// Program-defined error behind a failed instruction. Codes below
+// `ERROR_CODE_OFFSET` are Anchor's own and come back as None.
+pub fn vault_error(err: &InstructionError) -> Option<VaultError> {
+    lookup(VAULT_ERRORS, err)
+}
+
+pub fn escrow_error(err: &InstructionError) -> Option<EscrowError> {
+    lookup(ESCROW_ERRORS, err)
+}
+
+fn lookup<E: Copy>(errors: &[E], err: &InstructionError) -> Option<E> {
+    let InstructionError::Custom(code) = err else {
+        return None;
+    };
+    let index = code.checked_sub(ERROR_CODE_OFFSET)?;
+    errors.get(index as usize).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VAULT_SOURCE: &str =
+        include_str!("../../../week-3/simple-vault/programs/simple-vault/src/error.rs");
+    const ESCROW_SOURCE: &str =
+        include_str!("../../anchor-escrow/programs/anchor-escrow/src/error.rs");
+
+    // Every variant of both enums carries a `#[msg]`
+    fn variant_count(source: &str) -> u32 {
+        source.matches("#[msg(").count() as u32
+    }
+
+    #[test]
+    fn tables_follow_declaration_order() {
+        for (i, err) in VAULT_ERRORS.iter().enumerate() {
+            assert_eq!(u32::from(*err), ERROR_CODE_OFFSET + i as u32, "{err}");
+        }
+        for (i, err) in ESCROW_ERRORS.iter().enumerate() {
+            assert_eq!(u32::from(*err), ERROR_CODE_OFFSET + i as u32, "{err}");
+        }
+    }
+
+    // Catches a variant appended to a program but not to its table
+    #[test]
+    fn tables_end_on_the_last_variant() {
+        let last = VAULT_ERRORS.last().copied().map(u32::from);
+        assert_eq!(
+            last,
+            Some(ERROR_CODE_OFFSET + variant_count(VAULT_SOURCE) - 1)
+        );
+        let last = ESCROW_ERRORS.last().copied().map(u32::from);
+        assert_eq!(
+            last,
+            Some(ERROR_CODE_OFFSET + variant_count(ESCROW_SOURCE) - 1)
+        );
+    }
+}
@@ -0,0 +1,410 @@
+use anchor_escrow::{
+    accounts,
+    constants::{BASKET_SEED, CONFIG_SEED, ESCROW_SEED},
+    instruction as ix,
+    state::{BasketEscrow, Config, Escrow, Leg},
+    ID as PROGRAM_ID,
+};
+use anchor_lang::{
+    prelude::*,
+    solana_program::{bpf_loader_upgradeable, instruction::Instruction, system_program},
+};
+use anchor_spl::associated_token::{self, get_associated_token_address_with_program_id};
+
+pub fn escrow_address(maker: &Pubkey, seed: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[ESCROW_SEED, &seed.to_le_bytes(), maker.as_ref()],
+        &PROGRAM_ID,
+    )
+}
+
+pub fn config_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONFIG_SEED], &PROGRAM_ID)
+}
+
+pub fn basket_address(maker: &Pubkey, seed: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[BASKET_SEED, &seed.to_le_bytes(), maker.as_ref()],
+        &PROGRAM_ID,
+    )
+}
+
+// Program data account of the escrow, whose upgrade authority may initialize the config
+pub fn program_data_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PROGRAM_ID.as_ref()], &bpf_loader_upgradeable::ID)
+}
+
+pub fn decode_escrow(data: &[u8]) -> Result<Escrow> {
+    crate::decode(data)
+}
+
+pub fn decode_config(data: &[u8]) -> Result<Config> {
+    crate::decode(data)
+}
+
+pub fn decode_basket(data: &[u8]) -> Result<BasketEscrow> {
+    crate::decode(data)
+}
+
+// Signed by the program's upgrade authority, who becomes the config admin
+pub fn initialize_config(admin: &Pubkey, fee_bps: u16, treasury: Pubkey) -> Instruction {
+    crate::instruction(
+        PROGRAM_ID,
+        accounts::InitializeConfigAccounts {
+            admin: *admin,
+            config: config_address().0,
+            program: PROGRAM_ID,
+            program_data: program_data_address().0,
+            system_program: system_program::ID,
+        },
+        ix::InitializeConfig { fee_bps, treasury },
+    )
+}
+
+pub fn update_config(admin: &Pubkey, fee_bps: u16, treasury: Pubkey) -> Instruction {
+    crate::instruction(
+        PROGRAM_ID,
+        accounts::UpdateConfigAccounts {
+            admin: *admin,
+            config: config_address().0,
+        },
+        ix::UpdateConfig { fee_bps, treasury },
+    )
+}
+
+// Addresses of one single-pair escrow offer.
+//
+// For a Token-2022 mint with a transfer hook, append the hook program, its
+// validation account and the extra accounts it lists to the built instruction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EscrowKeys {
+    pub maker: Pubkey,
+    pub seed: u64,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub token_program: Pubkey,
+    pub escrow: Pubkey,
+    pub vault: Pubkey,
+}
+
+impl EscrowKeys {
+    pub fn new(
+        maker: Pubkey,
+        seed: u64,
+        mint_a: Pubkey,
+        mint_b: Pubkey,
+        token_program: Pubkey,
+    ) -> Self {
+        let (escrow, _) = escrow_address(&maker, seed);
+        EscrowKeys {
+            maker,
+            seed,
+            mint_a,
+            mint_b,
+            token_program,
+            escrow,
+            vault: get_associated_token_address_with_program_id(&escrow, &mint_a, &token_program),
+        }
+    }
+
+    // `token_program` isn't stored on the escrow; pass the owner of `mint_a`
+    pub fn from_escrow(escrow: &Escrow, token_program: Pubkey) -> Self {
+        Self::new(
+            escrow.maker,
+            escrow.seed,
+            escrow.mint_a,
+            escrow.mint_b,
+            token_program,
+        )
+    }
+
+    fn ata(&self, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(owner, mint, &self.token_program)
+    }
+
+    // `expires_at` of 0 never expires; `allowed_taker` of None is an open offer
+    pub fn make(
+        &self,
+        receive: u64,
+        deposit: u64,
+        expires_at: i64,
+        allowed_taker: Option<Pubkey>,
+    ) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::MakerAccounts {
+                maker: self.maker,
+                escrow: self.escrow,
+                mint_a: self.mint_a,
+                mint_b: self.mint_b,
+                vault: self.vault,
+                maker_ata_a: self.ata(&self.maker, &self.mint_a),
+                token_program: self.token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            ix::Make {
+                seed: self.seed,
+                receive,
+                deposit,
+                expires_at,
+                allowed_taker,
+            },
+        )
+    }
+
+    // `treasury` comes from the `Config` account, see `config_address`
+    pub fn take(&self, taker: &Pubkey, treasury: &Pubkey) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            self.taker_accounts(taker, treasury),
+            ix::Take {},
+        )
+    }
+
+    pub fn take_partial(&self, taker: &Pubkey, treasury: &Pubkey, amount_b: u64) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            self.taker_accounts(taker, treasury),
+            ix::TakePartial { amount_b },
+        )
+    }
+
+    fn taker_accounts(&self, taker: &Pubkey, treasury: &Pubkey) -> accounts::TakerAccounts {
+        accounts::TakerAccounts {
+            taker: *taker,
+            maker: self.maker,
+            escrow: self.escrow,
+            config: config_address().0,
+            treasury: *treasury,
+            mint_a: self.mint_a,
+            mint_b: self.mint_b,
+            vault: self.vault,
+            taker_ata_a: self.ata(taker, &self.mint_a),
+            taker_ata_b: self.ata(taker, &self.mint_b),
+            maker_ata_b: self.ata(&self.maker, &self.mint_b),
+            treasury_ata_b: self.ata(treasury, &self.mint_b),
+            token_program: self.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        }
+    }
+
+    // `new_mint_b` is only needed when the maker changes what they ask for
+    pub fn update_offer(
+        &self,
+        receive_amount: Option<u64>,
+        deposit_amount: Option<u64>,
+        new_mint_b: Option<Pubkey>,
+    ) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::UpdateOfferAccounts {
+                maker: self.maker,
+                escrow: self.escrow,
+                mint_a: self.mint_a,
+                new_mint_b,
+                vault: self.vault,
+                maker_ata_a: self.ata(&self.maker, &self.mint_a),
+                token_program: self.token_program,
+            },
+            ix::UpdateOffer {
+                receive_amount,
+                deposit_amount,
+            },
+        )
+    }
+
+    pub fn refund(&self) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::RefundAccounts {
+                maker: self.maker,
+                escrow: self.escrow,
+                mint_a: self.mint_a,
+                vault: self.vault,
+                maker_ata_a: self.ata(&self.maker, &self.mint_a),
+                token_program: self.token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            ix::Refund {},
+        )
+    }
+
+    // Permissionless; `payer` covers any extra rent for the larger layout
+    pub fn migrate(&self, payer: &Pubkey) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::MigrateEscrowAccounts {
+                payer: *payer,
+                escrow: self.escrow,
+                mint_a: self.mint_a,
+                vault: self.vault,
+                token_program: self.token_program,
+                system_program: system_program::ID,
+            },
+            ix::MigrateEscrow {},
+        )
+    }
+
+    // Permissionless once the offer has expired; `caller` pays for the maker's ATA if needed
+    pub fn expire(&self, caller: &Pubkey) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::ExpireAccounts {
+                caller: *caller,
+                maker: self.maker,
+                escrow: self.escrow,
+                mint_a: self.mint_a,
+                vault: self.vault,
+                maker_ata_a: self.ata(&self.maker, &self.mint_a),
+                token_program: self.token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            ix::Expire {},
+        )
+    }
+}
+
+// Addresses of one basket offer. Every leg must belong to `token_program`.
+//
+// Leg accounts go in `remaining_accounts` in the order the program reads them;
+// for hook mints, append the hook accounts to the built instruction.
+#[derive(Clone, PartialEq, Eq)]
+pub struct BasketKeys {
+    pub maker: Pubkey,
+    pub seed: u64,
+    pub offered: Vec<Leg>,
+    pub requested: Vec<Leg>,
+    pub token_program: Pubkey,
+    pub basket: Pubkey,
+}
+
+impl BasketKeys {
+    pub fn new(
+        maker: Pubkey,
+        seed: u64,
+        offered: Vec<Leg>,
+        requested: Vec<Leg>,
+        token_program: Pubkey,
+    ) -> Self {
+        let (basket, _) = basket_address(&maker, seed);
+        BasketKeys {
+            maker,
+            seed,
+            offered,
+            requested,
+            token_program,
+            basket,
+        }
+    }
+
+    pub fn from_basket(basket: &BasketEscrow, token_program: Pubkey) -> Self {
+        Self::new(
+            basket.maker,
+            basket.seed,
+            basket.offered.clone(),
+            basket.requested.clone(),
+            token_program,
+        )
+    }
+
+    fn ata(&self, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(owner, mint, &self.token_program)
+    }
+
+    // Vault holding one offered leg
+    pub fn vault(&self, mint: &Pubkey) -> Pubkey {
+        self.ata(&self.basket, mint)
+    }
+
+    // `[mint, maker_ata, vault]` per offered leg, then `[mint]` per requested leg
+    pub fn make(&self) -> Instruction {
+        let mut ix = crate::instruction(
+            PROGRAM_ID,
+            accounts::MakeBasketAccounts {
+                maker: self.maker,
+                basket: self.basket,
+                token_program: self.token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            ix::MakeBasket {
+                seed: self.seed,
+                offered: self.offered.clone(),
+                requested: self.requested.clone(),
+            },
+        );
+        for leg in &self.offered {
+            ix.accounts.extend([
+                AccountMeta::new_readonly(leg.mint, false),
+                AccountMeta::new(self.ata(&self.maker, &leg.mint), false),
+                AccountMeta::new(self.vault(&leg.mint), false),
+            ]);
+        }
+        for leg in &self.requested {
+            ix.accounts.push(AccountMeta::new_readonly(leg.mint, false));
+        }
+        ix
+    }
+
+    // `[mint, vault, taker_ata]` per offered leg, then
+    // `[mint, taker_ata, maker_ata, treasury_ata]` per requested leg.
+    // Offered mints are writable so transfer fees withheld in the vaults can be harvested.
+    pub fn take(&self, taker: &Pubkey, treasury: &Pubkey) -> Instruction {
+        let mut ix = crate::instruction(
+            PROGRAM_ID,
+            accounts::TakeBasketAccounts {
+                taker: *taker,
+                maker: self.maker,
+                basket: self.basket,
+                config: config_address().0,
+                treasury: *treasury,
+                token_program: self.token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            ix::TakeBasket {},
+        );
+        for leg in &self.offered {
+            ix.accounts.extend([
+                AccountMeta::new(leg.mint, false),
+                AccountMeta::new(self.vault(&leg.mint), false),
+                AccountMeta::new(self.ata(taker, &leg.mint), false),
+            ]);
+        }
+        for leg in &self.requested {
+            ix.accounts.extend([
+                AccountMeta::new_readonly(leg.mint, false),
+                AccountMeta::new(self.ata(taker, &leg.mint), false),
+                AccountMeta::new(self.ata(&self.maker, &leg.mint), false),
+                AccountMeta::new(self.ata(treasury, &leg.mint), false),
+            ]);
+        }
+        ix
+    }
+
+    // `[mint, vault, maker_ata]` per offered leg, mints writable as in `take`
+    pub fn refund(&self) -> Instruction {
+        let mut ix = crate::instruction(
+            PROGRAM_ID,
+            accounts::RefundBasketAccounts {
+                maker: self.maker,
+                basket: self.basket,
+                token_program: self.token_program,
+                system_program: system_program::ID,
+            },
+            ix::RefundBasket {},
+        );
+        for leg in &self.offered {
+            ix.accounts.extend([
+                AccountMeta::new(leg.mint, false),
+                AccountMeta::new(self.vault(&leg.mint), false),
+                AccountMeta::new(self.ata(&self.maker, &leg.mint), false),
+            ]);
+        }
+        ix
+    }
+}
@@ -0,0 +1,34 @@
+//! Typed client for the `simple_vault` and `anchor_escrow` programs.
+//!
+//! PDAs are derived from the programs' own seed constants and instructions are
+//! built from the Anchor-generated `instruction` and `accounts` types, so a
+//! change to either program shows up here as a compile error rather than a
+//! malformed transaction.
+pub mod error;
+pub mod escrow;
+pub mod vault;
+
+pub use anchor_escrow;
+pub use simple_vault;
+
+use anchor_lang::{
+    solana_program::instruction::Instruction, AccountDeserialize, InstructionData, Result,
+    ToAccountMetas,
+};
+
+fn instruction(
+    program_id: anchor_lang::prelude::Pubkey,
+    accounts: impl ToAccountMetas,
+    data: impl InstructionData,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+// Checks the discriminator before deserializing, like `Account<T>` does on-chain
+fn decode<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..])
+}
@@ -0,0 +1,832 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{instruction::Instruction, system_program, sysvar},
+};
+use anchor_spl::associated_token::{self, get_associated_token_address_with_program_id};
+use simple_vault::{
+    accounts,
+    constants::{
+        DELEGATE_SEED, PAYOUT_SEED, PROPOSAL_SEED, RECOVERY_SEED, STAKE_SEED, VAULT_SEED,
+        VAULT_STATE_SEED, VESTING_SEED,
+    },
+    instruction as ix,
+    state::{VaultState, VestingGrant, VestingTerms, WithdrawProposal},
+    ID as PROGRAM_ID,
+};
+use solana_stake_interface as stake;
+
+// vault_state PDA for the `vault_id`-th vault of `creator`
+pub fn vault_state_address(creator: &Pubkey, vault_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[VAULT_STATE_SEED, creator.as_ref(), &vault_id.to_le_bytes()],
+        &PROGRAM_ID,
+    )
+}
+
+// SOL-holding vault PDA belonging to `vault_state`
+pub fn vault_address(creator: &Pubkey, vault_state: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[VAULT_SEED, creator.as_ref(), vault_state.as_ref()],
+        &PROGRAM_ID,
+    )
+}
+
+pub fn delegate_address(vault_state: &Pubkey, delegate: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[DELEGATE_SEED, vault_state.as_ref(), delegate.as_ref()],
+        &PROGRAM_ID,
+    )
+}
+
+// `id` is the vault's `proposal_count` when the proposal was made
+pub fn proposal_address(vault_state: &Pubkey, id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PROPOSAL_SEED, vault_state.as_ref(), &id.to_le_bytes()],
+        &PROGRAM_ID,
+    )
+}
+
+pub fn payout_address(vault_state: &Pubkey, recipient: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PAYOUT_SEED, vault_state.as_ref(), recipient.as_ref()],
+        &PROGRAM_ID,
+    )
+}
+
+pub fn recovery_address(vault_state: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RECOVERY_SEED, vault_state.as_ref()], &PROGRAM_ID)
+}
+
+// The vault's one stake account, with the vault PDA as staker and withdrawer
+pub fn stake_address(vault_state: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[STAKE_SEED, vault_state.as_ref()], &PROGRAM_ID)
+}
+
+// SOL grants are keyed by beneficiary, token grants by beneficiary and mint
+pub fn vesting_address(
+    vault_state: &Pubkey,
+    beneficiary: &Pubkey,
+    mint: Option<&Pubkey>,
+) -> (Pubkey, u8) {
+    let mut seeds = vec![VESTING_SEED, vault_state.as_ref(), beneficiary.as_ref()];
+    if let Some(mint) = mint {
+        seeds.push(mint.as_ref());
+    }
+    Pubkey::find_program_address(&seeds, &PROGRAM_ID)
+}
+
+pub fn decode_vault_state(data: &[u8]) -> Result<VaultState> {
+    crate::decode(data)
+}
+
+pub fn decode_proposal(data: &[u8]) -> Result<WithdrawProposal> {
+    crate::decode(data)
+}
+
+pub fn decode_vesting_grant(data: &[u8]) -> Result<VestingGrant> {
+    crate::decode(data)
+}
+
+fn ata(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, mint, token_program)
+}
+
+// Addresses of one vault. The PDAs stay derived from the creator even after
+// ownership moves, so instructions signed by the current owner still need it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VaultKeys {
+    pub creator: Pubkey,
+    pub vault_id: u64,
+    pub vault_state: Pubkey,
+    pub vault: Pubkey,
+}
+
+impl VaultKeys {
+    pub fn new(creator: Pubkey, vault_id: u64) -> Self {
+        let (vault_state, _) = vault_state_address(&creator, vault_id);
+        let (vault, _) = vault_address(&creator, &vault_state);
+        VaultKeys {
+            creator,
+            vault_id,
+            vault_state,
+            vault,
+        }
+    }
+
+    pub fn from_state(state: &VaultState) -> Self {
+        Self::new(state.creator, state.vault_id)
+    }
+
+    // Signed by the creator; `unlock_at` of 0 leaves the vault unlocked
+    pub fn initialize_vault(&self, unlock_at: i64) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::InitializeAccounts {
+                signer: self.creator,
+                vault_state: self.vault_state,
+                vault: self.vault,
+                system_program: system_program::ID,
+            },
+            ix::InitializeVault {
+                vault_id: self.vault_id,
+                unlock_at,
+            },
+        )
+    }
+
+    pub fn deposit(&self, signer: &Pubkey, amount: u64) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::Deposit {
+                signer: *signer,
+                vault_state: self.vault_state,
+                vault: self.vault,
+                system_program: system_program::ID,
+            },
+            ix::Deposit { amount },
+        )
+    }
+
+    pub fn deposit_for(&self, depositor: &Pubkey, amount: u64) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::DepositForAccounts {
+                depositor: *depositor,
+                vault_state: self.vault_state,
+                vault: self.vault,
+                system_program: system_program::ID,
+            },
+            ix::DepositFor {
+                creator: self.creator,
+                amount,
+            },
+        )
+    }
+
+    pub fn withdraw(&self, owner: &Pubkey, amount: u64) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::WithdrawAccounts {
+                signer: *owner,
+                vault_state: self.vault_state,
+                vault: self.vault,
+                system_program: system_program::ID,
+            },
+            ix::Withdraw { amount },
+        )
+    }
+
+    pub fn withdraw_all(&self, owner: &Pubkey) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::WithdrawAllAccounts {
+                signer: *owner,
+                vault_state: self.vault_state,
+                vault: self.vault,
+                system_program: system_program::ID,
+            },
+            ix::WithdrawAll {},
+        )
+    }
+
+    pub fn close(&self, owner: &Pubkey) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::CloseAccounts {
+                signer: *owner,
+                vault_state: self.vault_state,
+                vault: self.vault,
+                system_program: system_program::ID,
+            },
+            ix::Close {},
+        )
+    }
+
+    // Pre-versioning vault of the creator, moved to `self.vault_id`
+    pub fn migrate_vault(&self) -> Instruction {
+        let (legacy_vault_state, _) =
+            Pubkey::find_program_address(&[VAULT_STATE_SEED, self.creator.as_ref()], &PROGRAM_ID);
+        let (legacy_vault, _) = vault_address(&self.creator, &legacy_vault_state);
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::MigrateVaultAccounts {
+                signer: self.creator,
+                legacy_vault_state,
+                legacy_vault,
+                vault_state: self.vault_state,
+                vault: self.vault,
+                system_program: system_program::ID,
+            },
+            ix::MigrateVault {
+                vault_id: self.vault_id,
+            },
+        )
+    }
+
+    pub fn extend_lock(&self, owner: &Pubkey, unlock_at: i64) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::ExtendLockAccounts {
+                signer: *owner,
+                vault_state: self.vault_state,
+            },
+            ix::ExtendLock { unlock_at },
+        )
+    }
+
+    // 0 means no cap; raising or removing a cap only applies after `CAP_RAISE_DELAY`
+    pub fn set_withdraw_cap(&self, owner: &Pubkey, cap: u64) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::SetWithdrawCapAccounts {
+                signer: *owner,
+                vault_state: self.vault_state,
+            },
+            ix::SetWithdrawCap { cap },
+        )
+    }
+
+    pub fn propose_new_owner(&self, owner: &Pubkey, new_owner: Pubkey) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::ProposeNewOwnerAccounts {
+                signer: *owner,
+                vault_state: self.vault_state,
+            },
+            ix::ProposeNewOwner { new_owner },
+        )
+    }
+
+    pub fn accept_ownership(&self, new_owner: &Pubkey) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::AcceptOwnershipAccounts {
+                signer: *new_owner,
+                vault_state: self.vault_state,
+            },
+            ix::AcceptOwnership {},
+        )
+    }
+
+    // Token vaults are the vault_state's ATAs, one per mint
+    pub fn token_vault(&self, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+        ata(&self.vault_state, mint, token_program)
+    }
+
+    pub fn deposit_token(
+        &self,
+        signer: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::DepositTokenAccounts {
+                signer: *signer,
+                vault_state: self.vault_state,
+                mint: *mint,
+                signer_ata: ata(signer, mint, token_program),
+                vault_ata: self.token_vault(mint, token_program),
+                token_program: *token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            ix::DepositToken { amount },
+        )
+    }
+
+    pub fn withdraw_token(
+        &self,
+        owner: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::WithdrawTokenAccounts {
+                signer: *owner,
+                vault_state: self.vault_state,
+                mint: *mint,
+                vault_ata: self.token_vault(mint, token_program),
+                signer_ata: ata(owner, mint, token_program),
+                token_program: *token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            ix::WithdrawToken { amount },
+        )
+    }
+
+    pub fn close_token_vault(
+        &self,
+        owner: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+    ) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::CloseTokenVaultAccounts {
+                signer: *owner,
+                vault_state: self.vault_state,
+                mint: *mint,
+                vault_ata: self.token_vault(mint, token_program),
+                signer_ata: ata(owner, mint, token_program),
+                token_program: *token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            ix::CloseTokenVault {},
+        )
+    }
+
+    pub fn approve_delegate(
+        &self,
+        owner: &Pubkey,
+        delegate: Pubkey,
+        allowance: u64,
+        per_epoch: bool,
+    ) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::ApproveDelegateAccounts {
+                signer: *owner,
+                vault_state: self.vault_state,
+                delegate_allowance: delegate_address(&self.vault_state, &delegate).0,
+                system_program: system_program::ID,
+            },
+            ix::ApproveDelegate {
+                delegate,
+                allowance,
+                per_epoch,
+            },
+        )
+    }
+
+    pub fn revoke_delegate(&self, owner: &Pubkey, delegate: &Pubkey) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::RevokeDelegateAccounts {
+                signer: *owner,
+                vault_state: self.vault_state,
+                delegate_allowance: delegate_address(&self.vault_state, delegate).0,
+            },
+            ix::RevokeDelegate {},
+        )
+    }
+
+    pub fn delegate_withdraw(&self, delegate: &Pubkey, amount: u64) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::DelegateWithdrawAccounts {
+                delegate: *delegate,
+                vault_state: self.vault_state,
+                delegate_allowance: delegate_address(&self.vault_state, delegate).0,
+                vault: self.vault,
+                system_program: system_program::ID,
+            },
+            ix::DelegateWithdraw { amount },
+        )
+    }
+
+    pub fn enable_multisig(
+        &self,
+        owner: &Pubkey,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::EnableMultisigAccounts {
+                signer: *owner,
+                vault_state: self.vault_state,
+            },
+            ix::EnableMultisig { signers, threshold },
+        )
+    }
+
+    // `id` must be the vault's current `proposal_count`
+    pub fn propose_withdraw(
+        &self,
+        proposer: &Pubkey,
+        id: u64,
+        recipient: Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::ProposeWithdrawAccounts {
+                proposer: *proposer,
+                vault_state: self.vault_state,
+                proposal: proposal_address(&self.vault_state, id).0,
+                system_program: system_program::ID,
+            },
+            ix::ProposeWithdraw { recipient, amount },
+        )
+    }
+
+    pub fn propose_token_withdraw(
+        &self,
+        proposer: &Pubkey,
+        id: u64,
+        mint: &Pubkey,
+        recipient: Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::ProposeTokenWithdrawAccounts {
+                proposer: *proposer,
+                vault_state: self.vault_state,
+                mint: *mint,
+                proposal: proposal_address(&self.vault_state, id).0,
+                system_program: system_program::ID,
+            },
+            ix::ProposeTokenWithdraw { recipient, amount },
+        )
+    }
+
+    pub fn approve_proposal(&self, signer: &Pubkey, id: u64) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::ApproveProposalAccounts {
+                signer: *signer,
+                vault_state: self.vault_state,
+                proposal: proposal_address(&self.vault_state, id).0,
+            },
+            ix::ApproveProposal {},
+        )
+    }
+
+    // Picks `execute_proposal` or `execute_token_proposal` from the proposal's mint.
+    // `token_program` is only used for token proposals; pass the owner of the mint.
+    pub fn execute_proposal(
+        &self,
+        signer: &Pubkey,
+        proposal: &WithdrawProposal,
+        token_program: &Pubkey,
+    ) -> Instruction {
+        let address = proposal_address(&self.vault_state, proposal.id).0;
+        let Some(mint) = proposal.mint else {
+            return crate::instruction(
+                PROGRAM_ID,
+                accounts::ExecuteProposalAccounts {
+                    signer: *signer,
+                    vault_state: self.vault_state,
+                    proposal: address,
+                    vault: self.vault,
+                    recipient: proposal.recipient,
+                    proposer: proposal.proposer,
+                    system_program: system_program::ID,
+                },
+                ix::ExecuteProposal {},
+            );
+        };
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::ExecuteTokenProposalAccounts {
+                signer: *signer,
+                vault_state: self.vault_state,
+                proposal: address,
+                mint,
+                vault_ata: self.token_vault(&mint, token_program),
+                recipient: proposal.recipient,
+                recipient_ata: ata(&proposal.recipient, &mint, token_program),
+                proposer: proposal.proposer,
+                token_program: *token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            ix::ExecuteTokenProposal {},
+        )
+    }
+
+    pub fn cancel_proposal(&self, signer: &Pubkey, proposal: &WithdrawProposal) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::CancelProposalAccounts {
+                signer: *signer,
+                vault_state: self.vault_state,
+                proposal: proposal_address(&self.vault_state, proposal.id).0,
+                proposer: proposal.proposer,
+            },
+            ix::CancelProposal {},
+        )
+    }
+
+    pub fn set_guardians(
+        &self,
+        owner: &Pubkey,
+        guardians: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::SetGuardiansAccounts {
+                signer: *owner,
+                vault_state: self.vault_state,
+            },
+            ix::SetGuardians {
+                guardians,
+                threshold,
+            },
+        )
+    }
+
+    pub fn start_recovery(&self, guardian: &Pubkey, new_owner: Pubkey) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::StartRecoveryAccounts {
+                guardian: *guardian,
+                vault_state: self.vault_state,
+                recovery: recovery_address(&self.vault_state).0,
+                system_program: system_program::ID,
+            },
+            ix::StartRecovery { new_owner },
+        )
+    }
+
+    pub fn approve_recovery(&self, guardian: &Pubkey) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::ApproveRecoveryAccounts {
+                guardian: *guardian,
+                vault_state: self.vault_state,
+                recovery: recovery_address(&self.vault_state).0,
+            },
+            ix::ApproveRecovery {},
+        )
+    }
+
+    // `initiator` is the guardian who started the recovery and gets its rent back
+    pub fn veto_recovery(&self, owner: &Pubkey, initiator: &Pubkey) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::VetoRecoveryAccounts {
+                signer: *owner,
+                vault_state: self.vault_state,
+                recovery: recovery_address(&self.vault_state).0,
+                initiator: *initiator,
+            },
+            ix::VetoRecovery {},
+        )
+    }
+
+    pub fn complete_recovery(&self, initiator: &Pubkey) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::CompleteRecoveryAccounts {
+                vault_state: self.vault_state,
+                recovery: recovery_address(&self.vault_state).0,
+                initiator: *initiator,
+            },
+            ix::CompleteRecovery {},
+        )
+    }
+
+    // The initiator at any time, or another guardian once the request is stale
+    pub fn cancel_recovery(&self, signer: &Pubkey, initiator: &Pubkey) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::CancelRecoveryAccounts {
+                signer: *signer,
+                vault_state: self.vault_state,
+                recovery: recovery_address(&self.vault_state).0,
+                initiator: *initiator,
+            },
+            ix::CancelRecovery {},
+        )
+    }
+
+    // `amount` covers the stake account's rent as well as the delegation
+    pub fn delegate_stake(&self, owner: &Pubkey, vote_account: Pubkey, amount: u64) -> Instruction {
+        #[allow(deprecated)]
+        let stake_config = stake::config::ID;
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::DelegateStakeAccounts {
+                signer: *owner,
+                vault_state: self.vault_state,
+                vault: self.vault,
+                stake_account: stake_address(&self.vault_state).0,
+                vote_account,
+                stake_config,
+                stake_history: sysvar::stake_history::ID,
+                clock: sysvar::clock::ID,
+                rent: sysvar::rent::ID,
+                stake_program: stake::program::ID,
+                system_program: system_program::ID,
+            },
+            ix::DelegateStake { amount },
+        )
+    }
+
+    pub fn deactivate_stake(&self, owner: &Pubkey) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::DeactivateStakeAccounts {
+                signer: *owner,
+                vault_state: self.vault_state,
+                vault: self.vault,
+                stake_account: stake_address(&self.vault_state).0,
+                clock: sysvar::clock::ID,
+                stake_program: stake::program::ID,
+            },
+            ix::DeactivateStake {},
+        )
+    }
+
+    // Withdraws the whole stake account back into the vault once it has cooled down
+    pub fn withdraw_stake(&self, owner: &Pubkey) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::WithdrawStakeAccounts {
+                signer: *owner,
+                vault_state: self.vault_state,
+                vault: self.vault,
+                stake_account: stake_address(&self.vault_state).0,
+                stake_history: sysvar::stake_history::ID,
+                clock: sysvar::clock::ID,
+                stake_program: stake::program::ID,
+            },
+            ix::WithdrawStake {},
+        )
+    }
+
+    pub fn create_payout(
+        &self,
+        owner: &Pubkey,
+        recipient: Pubkey,
+        amount_per_period: u64,
+        period: i64,
+        end_at: i64,
+    ) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::CreatePayoutAccounts {
+                signer: *owner,
+                vault_state: self.vault_state,
+                payout: payout_address(&self.vault_state, &recipient).0,
+                system_program: system_program::ID,
+            },
+            ix::CreatePayout {
+                recipient,
+                amount_per_period,
+                period,
+                end_at,
+            },
+        )
+    }
+
+    // Permissionless, so it has no signer; the fee payer signs the transaction
+    pub fn crank_payout(&self, recipient: &Pubkey) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::CrankPayoutAccounts {
+                vault_state: self.vault_state,
+                vault: self.vault,
+                payout: payout_address(&self.vault_state, recipient).0,
+                recipient: *recipient,
+                system_program: system_program::ID,
+            },
+            ix::CrankPayout {},
+        )
+    }
+
+    pub fn cancel_payout(&self, owner: &Pubkey, recipient: &Pubkey) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::CancelPayoutAccounts {
+                signer: *owner,
+                vault_state: self.vault_state,
+                vault: self.vault,
+                payout: payout_address(&self.vault_state, recipient).0,
+                recipient: *recipient,
+                system_program: system_program::ID,
+            },
+            ix::CancelPayout {},
+        )
+    }
+
+    pub fn create_vesting(
+        &self,
+        owner: &Pubkey,
+        beneficiary: Pubkey,
+        terms: VestingTerms,
+    ) -> Instruction {
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::CreateVestingAccounts {
+                signer: *owner,
+                vault_state: self.vault_state,
+                vault: self.vault,
+                grant: vesting_address(&self.vault_state, &beneficiary, None).0,
+                system_program: system_program::ID,
+            },
+            ix::CreateVesting { beneficiary, terms },
+        )
+    }
+
+    pub fn create_token_vesting(
+        &self,
+        owner: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        beneficiary: Pubkey,
+        terms: VestingTerms,
+    ) -> Instruction {
+        let (grant, _) = vesting_address(&self.vault_state, &beneficiary, Some(mint));
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::CreateTokenVestingAccounts {
+                signer: *owner,
+                vault_state: self.vault_state,
+                mint: *mint,
+                vault_ata: self.token_vault(mint, token_program),
+                grant,
+                grant_ata: ata(&grant, mint, token_program),
+                token_program: *token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            ix::CreateTokenVesting { beneficiary, terms },
+        )
+    }
+
+    // Picks `claim_vested` or `claim_vested_token` from the grant's mint.
+    // `token_program` is only used for token grants; pass the owner of the mint.
+    pub fn claim_vested(&self, grant: &VestingGrant, token_program: &Pubkey) -> Instruction {
+        let (address, _) =
+            vesting_address(&self.vault_state, &grant.beneficiary, grant.mint.as_ref());
+        let Some(mint) = grant.mint else {
+            return crate::instruction(
+                PROGRAM_ID,
+                accounts::ClaimVestedAccounts {
+                    beneficiary: grant.beneficiary,
+                    grant: address,
+                    funder: grant.funder,
+                },
+                ix::ClaimVested {},
+            );
+        };
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::ClaimVestedTokenAccounts {
+                beneficiary: grant.beneficiary,
+                grant: address,
+                mint,
+                grant_ata: ata(&address, &mint, token_program),
+                beneficiary_ata: ata(&grant.beneficiary, &mint, token_program),
+                funder: grant.funder,
+                token_program: *token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            ix::ClaimVestedToken {},
+        )
+    }
+
+    // Picks `revoke_vesting` or `revoke_token_vesting` from the grant's mint
+    pub fn revoke_vesting(
+        &self,
+        owner: &Pubkey,
+        grant: &VestingGrant,
+        token_program: &Pubkey,
+    ) -> Instruction {
+        let (address, _) =
+            vesting_address(&self.vault_state, &grant.beneficiary, grant.mint.as_ref());
+        let Some(mint) = grant.mint else {
+            return crate::instruction(
+                PROGRAM_ID,
+                accounts::RevokeVestingAccounts {
+                    signer: *owner,
+                    vault_state: self.vault_state,
+                    vault: self.vault,
+                    grant: address,
+                    beneficiary: grant.beneficiary,
+                    funder: grant.funder,
+                },
+                ix::RevokeVesting {},
+            );
+        };
+        crate::instruction(
+            PROGRAM_ID,
+            accounts::RevokeTokenVestingAccounts {
+                signer: *owner,
+                vault_state: self.vault_state,
+                mint,
+                vault_ata: self.token_vault(&mint, token_program),
+                grant: address,
+                grant_ata: ata(&address, &mint, token_program),
+                beneficiary: grant.beneficiary,
+                beneficiary_ata: ata(&grant.beneficiary, &mint, token_program),
+                funder: grant.funder,
+                token_program: *token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            ix::RevokeTokenVesting {},
+        )
+    }
+}
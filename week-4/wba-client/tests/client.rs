@@ -0,0 +1,338 @@
+use anchor_lang::{prelude::*, Discriminator};
+use anchor_spl::{associated_token::get_associated_token_address_with_program_id, token};
+use solana_instruction::error::InstructionError;
+use wba_client::{
+    anchor_escrow::{
+        self,
+        error::EscrowError,
+        state::{Escrow, EscrowStatus, Leg},
+    },
+    error::{escrow_error, vault_error},
+    escrow::{config_address, decode_escrow, BasketKeys, EscrowKeys},
+    simple_vault::{
+        self,
+        error::VaultError,
+        state::{VaultState, VestingGrant, WithdrawProposal},
+    },
+    vault::{decode_vault_state, proposal_address, stake_address, vesting_address, VaultKeys},
+};
+
+fn vault_state(creator: Pubkey, vault_id: u64) -> VaultState {
+    VaultState {
+        creator,
+        owner: creator,
+        pending_owner: None,
+        owner_nonce: 0,
+        vault_id,
+        state_bump: 0,
+        vault_bump: 0,
+        unlock_at: 0,
+        signers: Vec::new(),
+        threshold: 0,
+        proposal_count: 0,
+        withdraw_cap: 0,
+        window_start: 0,
+        window_spent: 0,
+        pending_cap: 0,
+        pending_cap_at: 0,
+        guardians: Vec::new(),
+        guardian_threshold: 0,
+        staked: 0,
+        total_deposited: 5,
+        total_withdrawn: 2,
+        last_activity_slot: 42,
+    }
+}
+
+#[test]
+fn vault_keys_follow_the_program_seeds() {
+    let creator = Pubkey::new_unique();
+    let keys = VaultKeys::new(creator, 3);
+
+    let (vault_state, _) = Pubkey::find_program_address(
+        &[b"vault_state", creator.as_ref(), &3u64.to_le_bytes()],
+        &simple_vault::ID,
+    );
+    let (vault, _) = Pubkey::find_program_address(
+        &[b"vault", creator.as_ref(), vault_state.as_ref()],
+        &simple_vault::ID,
+    );
+    assert_eq!(keys.vault_state, vault_state);
+    assert_eq!(keys.vault, vault);
+    assert_ne!(keys, VaultKeys::new(creator, 4));
+}
+
+#[test]
+fn vault_instructions_sign_with_the_owner_but_derive_from_the_creator() {
+    let creator = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let keys = VaultKeys::new(creator, 0);
+
+    let ix = keys.withdraw(&owner, 10);
+    assert_eq!(ix.program_id, simple_vault::ID);
+    assert_eq!(ix.accounts[0].pubkey, owner);
+    assert!(ix.accounts[0].is_signer);
+    assert_eq!(ix.accounts[1].pubkey, keys.vault_state);
+    assert_eq!(ix.accounts[2].pubkey, keys.vault);
+    assert!(ix
+        .data
+        .starts_with(simple_vault::instruction::Withdraw::DISCRIMINATOR));
+    assert!(ix.data.ends_with(&10u64.to_le_bytes()));
+
+    let ix = keys.deposit_for(&owner, 7);
+    let args = &ix.data[simple_vault::instruction::DepositFor::DISCRIMINATOR.len()..];
+    assert_eq!(&args[..32], creator.as_ref());
+    assert_eq!(&args[32..], &7u64.to_le_bytes());
+}
+
+#[test]
+fn stake_instructions_share_the_vault_stake_account() {
+    let owner = Pubkey::new_unique();
+    let vote_account = Pubkey::new_unique();
+    let keys = VaultKeys::new(Pubkey::new_unique(), 0);
+    let (stake_account, _) = stake_address(&keys.vault_state);
+
+    let ix = keys.delegate_stake(&owner, vote_account, 5);
+    assert_eq!(ix.accounts[0].pubkey, owner);
+    assert_eq!(ix.accounts[3].pubkey, stake_account);
+    assert!(ix.accounts[3].is_writable);
+    assert_eq!(ix.accounts[4].pubkey, vote_account);
+    assert!(ix.data.ends_with(&5u64.to_le_bytes()));
+
+    for ix in [keys.deactivate_stake(&owner), keys.withdraw_stake(&owner)] {
+        assert_eq!(ix.accounts[2].pubkey, keys.vault);
+        assert_eq!(ix.accounts[3].pubkey, stake_account);
+    }
+}
+
+#[test]
+fn vault_state_round_trips_and_rejects_other_accounts() {
+    let creator = Pubkey::new_unique();
+    let mut data = Vec::new();
+    vault_state(creator, 9).try_serialize(&mut data).unwrap();
+
+    let decoded = decode_vault_state(&data).unwrap();
+    assert_eq!(VaultKeys::from_state(&decoded), VaultKeys::new(creator, 9));
+    assert_eq!(decoded.total_deposited, 5);
+    assert_eq!(decoded.total_withdrawn, 2);
+    assert_eq!(decoded.last_activity_slot, 42);
+
+    data[..8].copy_from_slice(Escrow::DISCRIMINATOR);
+    assert!(decode_vault_state(&data).is_err());
+}
+
+#[test]
+fn token_grants_are_keyed_by_mint_and_claim_from_their_ata() {
+    let keys = VaultKeys::new(Pubkey::new_unique(), 0);
+    let beneficiary = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+
+    let (sol_grant, _) = vesting_address(&keys.vault_state, &beneficiary, None);
+    let (token_grant, _) = vesting_address(&keys.vault_state, &beneficiary, Some(&mint));
+    let (expected, _) = Pubkey::find_program_address(
+        &[
+            b"vesting",
+            keys.vault_state.as_ref(),
+            beneficiary.as_ref(),
+            mint.as_ref(),
+        ],
+        &simple_vault::ID,
+    );
+    assert_eq!(token_grant, expected);
+    assert_ne!(token_grant, sol_grant);
+
+    let mut grant = VestingGrant {
+        vault_state: keys.vault_state,
+        funder: Pubkey::new_unique(),
+        beneficiary,
+        mint: Some(mint),
+        total_amount: 100,
+        claimed: 0,
+        start_at: 0,
+        cliff_at: 0,
+        duration: 10,
+        revocable: true,
+        bump: 0,
+    };
+    let ix = keys.claim_vested(&grant, &token::ID);
+    assert!(ix
+        .data
+        .starts_with(simple_vault::instruction::ClaimVestedToken::DISCRIMINATOR));
+    assert_eq!(ix.accounts[1].pubkey, token_grant);
+    let grant_ata = get_associated_token_address_with_program_id(&token_grant, &mint, &token::ID);
+    assert!(ix.accounts.iter().any(|meta| meta.pubkey == grant_ata));
+
+    grant.mint = None;
+    let ix = keys.claim_vested(&grant, &token::ID);
+    assert!(ix
+        .data
+        .starts_with(simple_vault::instruction::ClaimVested::DISCRIMINATOR));
+    assert_eq!(ix.accounts[1].pubkey, sol_grant);
+}
+
+#[test]
+fn proposals_execute_from_the_asset_they_name() {
+    let keys = VaultKeys::new(Pubkey::new_unique(), 0);
+    let member = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let mut proposal = WithdrawProposal {
+        vault_state: keys.vault_state,
+        proposer: member,
+        id: 4,
+        recipient: Pubkey::new_unique(),
+        amount: 10,
+        mint: None,
+        approvals: vec![member],
+        expires_at: 0,
+        bump: 0,
+    };
+    let (address, _) = proposal_address(&keys.vault_state, 4);
+    assert_eq!(
+        keys.approve_proposal(&member, 4).accounts[2].pubkey,
+        address
+    );
+
+    let ix = keys.execute_proposal(&member, &proposal, &token::ID);
+    assert!(ix
+        .data
+        .starts_with(simple_vault::instruction::ExecuteProposal::DISCRIMINATOR));
+    assert!(ix.accounts.iter().any(|meta| meta.pubkey == keys.vault));
+
+    proposal.mint = Some(mint);
+    let ix = keys.execute_proposal(&member, &proposal, &token::ID);
+    assert!(ix
+        .data
+        .starts_with(simple_vault::instruction::ExecuteTokenProposal::DISCRIMINATOR));
+    assert!(ix
+        .accounts
+        .iter()
+        .any(|meta| meta.pubkey == keys.token_vault(&mint, &token::ID)));
+}
+
+#[test]
+fn escrow_keys_match_the_decoded_escrow() {
+    let maker = Pubkey::new_unique();
+    let mint_a = Pubkey::new_unique();
+    let mint_b = Pubkey::new_unique();
+    let keys = EscrowKeys::new(maker, 11, mint_a, mint_b, token::ID);
+
+    let (escrow, bump) = Pubkey::find_program_address(
+        &[b"escrow", &11u64.to_le_bytes(), maker.as_ref()],
+        &anchor_escrow::ID,
+    );
+    assert_eq!(keys.escrow, escrow);
+    assert_eq!(
+        keys.vault,
+        get_associated_token_address_with_program_id(&escrow, &mint_a, &token::ID)
+    );
+
+    let mut data = Vec::new();
+    Escrow {
+        version: 1,
+        maker,
+        mint_a,
+        mint_b,
+        receive_amount: 100,
+        deposit_amount: 50,
+        remaining_amount: 50,
+        filled_amount: 0,
+        expires_at: 0,
+        allowed_taker: None,
+        seed: 11,
+        bump,
+        status: EscrowStatus::Deposited,
+        reserved: [0; 64],
+    }
+    .try_serialize(&mut data)
+    .unwrap();
+    let decoded = decode_escrow(&data).unwrap();
+    assert_eq!(EscrowKeys::from_escrow(&decoded, token::ID), keys);
+}
+
+#[test]
+fn take_pays_the_maker_and_treasury_in_token_b() {
+    let maker = Pubkey::new_unique();
+    let taker = Pubkey::new_unique();
+    let treasury = Pubkey::new_unique();
+    let mint_b = Pubkey::new_unique();
+    let keys = EscrowKeys::new(maker, 0, Pubkey::new_unique(), mint_b, token::ID);
+
+    let ix = keys.take_partial(&taker, &treasury, 25);
+    let signers: Vec<_> = ix.accounts.iter().filter(|meta| meta.is_signer).collect();
+    assert_eq!(signers.len(), 1);
+    assert_eq!(signers[0].pubkey, taker);
+    for owner in [maker, treasury] {
+        let ata = get_associated_token_address_with_program_id(&owner, &mint_b, &token::ID);
+        assert!(ix.accounts.iter().any(|meta| meta.pubkey == ata));
+    }
+    assert!(ix
+        .accounts
+        .iter()
+        .any(|meta| meta.pubkey == config_address().0));
+    assert!(ix.data.ends_with(&25u64.to_le_bytes()));
+}
+
+#[test]
+fn basket_take_lists_every_leg_after_the_named_accounts() {
+    let maker = Pubkey::new_unique();
+    let taker = Pubkey::new_unique();
+    let treasury = Pubkey::new_unique();
+    let offered: Vec<Leg> = (0..2)
+        .map(|_| Leg {
+            mint: Pubkey::new_unique(),
+            amount: 5,
+        })
+        .collect();
+    let requested = vec![Leg {
+        mint: Pubkey::new_unique(),
+        amount: 7,
+    }];
+    let keys = BasketKeys::new(maker, 1, offered.clone(), requested.clone(), token::ID);
+
+    let ix = keys.take(&taker, &treasury);
+    let named = anchor_escrow::accounts::TakeBasketAccounts {
+        taker,
+        maker,
+        basket: keys.basket,
+        config: config_address().0,
+        treasury,
+        token_program: token::ID,
+        associated_token_program: Pubkey::default(),
+        system_program: Pubkey::default(),
+    }
+    .to_account_metas(None)
+    .len();
+    let legs = &ix.accounts[named..];
+    assert_eq!(legs.len(), 3 * offered.len() + 4 * requested.len());
+
+    for (leg, metas) in offered.iter().zip(legs.chunks(3)) {
+        assert_eq!(metas[0].pubkey, leg.mint);
+        assert!(metas[0].is_writable);
+        assert_eq!(metas[1].pubkey, keys.vault(&leg.mint));
+    }
+    let metas = &legs[3 * offered.len()..];
+    assert_eq!(metas[0].pubkey, requested[0].mint);
+    assert!(!metas[0].is_writable);
+    assert_eq!(
+        metas[3].pubkey,
+        get_associated_token_address_with_program_id(&treasury, &requested[0].mint, &token::ID)
+    );
+
+    let make = keys.make();
+    assert_eq!(make.accounts.len() - 5, 3 * offered.len() + requested.len());
+    assert_eq!(make.accounts.last().unwrap().pubkey, requested[0].mint);
+}
+
+#[test]
+fn custom_codes_decode_to_program_errors() {
+    let err = vault_error(&InstructionError::Custom(VaultError::VaultLocked.into()));
+    assert_eq!(err.map(u32::from), Some(VaultError::VaultLocked.into()));
+
+    let err = escrow_error(&InstructionError::Custom(EscrowError::EscrowExpired.into()));
+    assert_eq!(err.map(u32::from), Some(EscrowError::EscrowExpired.into()));
+
+    // Anchor's own constraint errors and non-custom failures aren't program errors
+    assert!(vault_error(&InstructionError::Custom(2006)).is_none());
+    assert!(escrow_error(&InstructionError::MissingRequiredSignature).is_none());
+    assert!(vault_error(&InstructionError::Custom(6999)).is_none());
+}